@@ -0,0 +1,165 @@
+use crate::pipe::{Cardinal, Pipe, ALL_DIRECTIONS};
+use std::str::FromStr;
+
+use Cardinal::*;
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum GridError {
+    UnknownChar(char),
+    RaggedLine(usize),
+    NoStart,
+    MultipleStarts,
+    /// Fewer than two neighbours connect to the start tile.
+    StartNotConnected,
+    /// No shape of the start tile closes a loop.
+    NoLoop,
+    /// Several shapes of the start tile close a loop.
+    AmbiguousStart(Vec<Pipe>),
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub struct Grid {
+    pub height: usize,
+    pub width: usize,
+    pub elements: Vec<Pipe>,
+    /// Index of the `S` tile, whose real pipe is stored in `elements`.
+    pub start: usize,
+}
+
+/// Index next to `old_index` in a `width` x `height` grid, if it is still inside.
+pub fn neighbour(
+    old_index: usize,
+    direction: Cardinal,
+    width: usize,
+    height: usize,
+) -> Option<usize> {
+    let new_index = match direction {
+        N => old_index.checked_sub(width),
+        E if (old_index + 1).is_multiple_of(width) => None,
+        E => Some(old_index + 1),
+        S => Some(old_index + width),
+        W if old_index.is_multiple_of(width) => None,
+        W => Some(old_index - 1),
+    };
+    new_index.filter(|&i| i < width * height)
+}
+
+impl FromStr for Grid {
+    type Err = GridError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = crate::render::strip_ansi(s);
+        let lines: Vec<&str> = s.lines().collect();
+        let height = lines.len();
+        let width = lines.first().map_or(0, |line| line.chars().count());
+        let mut elements = Vec::with_capacity(width * height);
+        for (line_i, line) in lines.iter().enumerate() {
+            if line.chars().count() != width {
+                return Err(GridError::RaggedLine(line_i));
+            }
+            for c in line.chars() {
+                let pipe = Pipe::from_str(&c.to_string()).map_err(|_| GridError::UnknownChar(c))?;
+                elements.push(pipe);
+            }
+        }
+        let mut iter_starts = elements
+            .iter()
+            .enumerate()
+            .filter(|(_, &pipe)| pipe == Pipe::Start)
+            .map(|(i, _)| i);
+        let start = iter_starts.next().ok_or(GridError::NoStart)?;
+        if iter_starts.next().is_some() {
+            return Err(GridError::MultipleStarts);
+        }
+        let mut grid = Grid {
+            height,
+            width,
+            elements,
+            start,
+        };
+        grid.elements[start] = grid.infer_start()?;
+        Ok(grid)
+    }
+}
+
+impl Grid {
+    pub fn get_new_index(&self, old_index: usize, direction: Cardinal) -> Option<usize> {
+        neighbour(old_index, direction, self.width, self.height)
+    }
+
+    /// Deduces the pipe under the start tile from the neighbours connecting to it.
+    fn infer_start(&mut self) -> Result<Pipe, GridError> {
+        let connected: Vec<Cardinal> = ALL_DIRECTIONS
+            .into_iter()
+            .filter(|&d| {
+                self.get_new_index(self.start, d)
+                    .is_some_and(|i| self.elements[i].has_cardinal(d.opposite()))
+            })
+            .collect();
+        if connected.len() < 2 {
+            return Err(GridError::StartNotConnected);
+        }
+        let candidates: Vec<Pipe> = connected
+            .iter()
+            .enumerate()
+            .flat_map(|(i, &first)| {
+                connected[i + 1..]
+                    .iter()
+                    .filter_map(move |&second| Pipe::from_cardinals(first, second))
+            })
+            .collect();
+        let closing: Vec<Pipe> = candidates
+            .into_iter()
+            .filter(|&pipe| {
+                self.elements[self.start] = pipe;
+                self.follow_loop(self.start).is_some()
+            })
+            .collect();
+        self.elements[self.start] = Pipe::Start;
+        match closing[..] {
+            [pipe] => Ok(pipe),
+            [] => Err(GridError::NoLoop),
+            _ => Err(GridError::AmbiguousStart(closing)),
+        }
+    }
+
+    /// Indices of the loop going through `start`, in walking order, if the pipes close one.
+    pub fn follow_loop(&self, start: usize) -> Option<Vec<usize>> {
+        let mut direction = ALL_DIRECTIONS
+            .into_iter()
+            .find(|&d| self.elements[start].has_cardinal(d))?;
+        let mut current_index = start;
+        let mut indices = vec![start];
+        loop {
+            current_index = self.get_new_index(current_index, direction)?;
+            let entry = direction.opposite();
+            if current_index == start {
+                return self.elements[start].has_cardinal(entry).then_some(indices);
+            }
+            direction = self.elements[current_index].exit(entry)?;
+            indices.push(current_index);
+        }
+    }
+
+    /// Tiles strictly inside a loop given in walking order.
+    /// The shoelace formula gives the area of the polygon through the tile centres,
+    /// then Pick's theorem `A = I + B / 2 - 1` gives the `I` tiles inside.
+    pub fn enclosed_tiles(&self, loop_indices: &[usize]) -> usize {
+        let coordinates: Vec<(i64, i64)> = loop_indices
+            .iter()
+            .map(|&i| ((i % self.width) as i64, (i / self.width) as i64))
+            .collect();
+        let twice_area: i64 = coordinates
+            .iter()
+            .zip(coordinates.iter().cycle().skip(1))
+            .map(|(&(x_1, y_1), &(x_2, y_2))| x_1 * y_2 - x_2 * y_1)
+            .sum();
+        let boundary = loop_indices.len() as i64;
+        ((twice_area.abs() - boundary + 2) / 2) as usize
+    }
+
+    /// Indices of the loop going through the start tile.
+    pub fn loop_indices(&self) -> Vec<usize> {
+        self.follow_loop(self.start)
+            .expect("the start shape was checked when parsing")
+    }
+}
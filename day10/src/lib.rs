@@ -0,0 +1,178 @@
+mod grid;
+mod loops;
+mod marks;
+mod pipe;
+mod render;
+
+pub use grid::{Grid, GridError};
+pub use loops::{Fragment, PipeLoop, PipeNetwork};
+pub use marks::{Mark, MarkedGrid, MarkedPipe, Regions};
+pub use pipe::{Cardinal, Pipe};
+pub use render::strip_ansi;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn parse(input: &str) -> Grid {
+        Grid::from_str(input).unwrap()
+    }
+
+    #[test]
+    fn start_shape() {
+        let grid = parse(include_str!("../input_test_1.txt"));
+        assert_eq!(grid.elements[grid.start], Pipe::SE);
+        let grid = parse(include_str!("../input_test_2.txt"));
+        assert_eq!(grid.elements[grid.start], Pipe::SE);
+        assert_eq!(grid.loop_indices().len() / 2, 8);
+        let grid = parse(include_str!("../input_test_6.txt"));
+        assert_eq!(grid.elements[grid.start], Pipe::SW);
+    }
+
+    #[test]
+    fn ambiguous_start() {
+        // All four neighbours connect to S, which closes two loops as J and as F.
+        let input = ".F-7.\n.|.|.\n.L-S7\n...||\n...LJ";
+        assert_eq!(
+            Grid::from_str(input).err(),
+            Some(GridError::AmbiguousStart(vec![Pipe::NW, Pipe::SE]))
+        );
+        // Three neighbours connect to S but only L closes a loop.
+        let input = ".F-7.\n.|.|.\n-S-J.\n.....";
+        assert_eq!(parse(input).elements[11], Pipe::NE);
+        let input = ".F-7.\n.|.|.\n.S.|.\n.|.|.\n.L-J.";
+        assert_eq!(parse(input).elements[11], Pipe::NS);
+    }
+
+    #[test]
+    fn start_errors() {
+        assert_eq!(
+            Grid::from_str(".....\n.S-7.\n...|.\n.L-J.").err(),
+            Some(GridError::StartNotConnected)
+        );
+        assert_eq!(
+            Grid::from_str("F-7\n|.|\nL-J").err(),
+            Some(GridError::NoStart)
+        );
+        assert_eq!(
+            Grid::from_str("S-7\n|.|\nL-X").err(),
+            Some(GridError::UnknownChar('X'))
+        );
+        assert_eq!(
+            Grid::from_str("S-7\n|.|\nL-.").err(),
+            Some(GridError::NoLoop)
+        );
+    }
+
+    #[test]
+    fn enclosed_tiles() {
+        for (input, expected) in [
+            (include_str!("../input_test_3.txt"), 4),
+            (include_str!("../input_test_4.txt"), 4),
+            (include_str!("../input_test_5.txt"), 8),
+            (include_str!("../input_test_6.txt"), 10),
+        ] {
+            let grid = parse(input);
+            let mut extended_grid = MarkedGrid::mark_loop(&grid).extend_grid();
+            while extended_grid.try_mark() {}
+            assert_eq!(extended_grid.count_inside(), expected);
+            assert_eq!(grid.enclosed_tiles(&grid.loop_indices()), expected);
+        }
+    }
+
+    #[test]
+    fn shoelace_matches_flood_fill() {
+        for input in [
+            include_str!("../input_test_1.txt"),
+            include_str!("../input_test_2.txt"),
+            include_str!("../input.txt"),
+        ] {
+            let grid = parse(input);
+            let mut extended_grid = MarkedGrid::mark_loop(&grid).extend_grid();
+            while extended_grid.try_mark() {}
+            assert_eq!(
+                grid.enclosed_tiles(&grid.loop_indices()),
+                extended_grid.count_inside()
+            );
+        }
+    }
+
+    #[test]
+    fn cell_queries_and_regions() {
+        let grid = parse(include_str!("../input_test_3.txt"));
+        let marked_grid = MarkedGrid::classify(&grid);
+        assert_eq!(
+            (marked_grid.height, marked_grid.width),
+            (grid.height, grid.width)
+        );
+        assert_eq!(marked_grid.mark_at(0, 0), Some(Mark::Outside));
+        assert_eq!(marked_grid.mark_at(1, 1), Some(Mark::Loop));
+        assert_eq!(marked_grid.mark_at(6, 2), Some(Mark::Inside));
+        assert_eq!(marked_grid.mark_at(3, 3), Some(Mark::Outside));
+        assert_eq!(marked_grid.mark_at(9, 0), None);
+        let regions = marked_grid.inside_regions();
+        assert_eq!(regions.sizes, vec![2, 2]);
+        assert_eq!(regions.label_at(6, 2), Some(0));
+        assert_eq!(regions.label_at(6, 7), Some(1));
+        assert_eq!(regions.label_at(0, 0), None);
+        assert_eq!(
+            marked_grid.export_marks().lines().nth(6),
+            Some("OLIILOLIILO")
+        );
+    }
+
+    #[test]
+    fn every_loop_and_fragment() {
+        let grid = parse("S7.F-7\nLJ.|.|\n-..L-J\n.F7...\n.|....");
+        let network = grid.analyse_pipes();
+        assert_eq!(network.loops.len(), 2);
+        assert_eq!(network.loops[0].indices, vec![0, 1, 7, 6]);
+        assert_eq!(network.loops[0].enclosed, 0);
+        assert_eq!(network.loops[1].length(), 8);
+        assert_eq!(network.loops[1].farthest, 17);
+        assert_eq!(network.loops[1].farthest_steps(), 4);
+        assert_eq!(network.loops[1].enclosed, 1);
+        assert_eq!(
+            network.fragments,
+            vec![
+                Fragment {
+                    indices: vec![12],
+                    open_ends: vec![(12, Cardinal::E), (12, Cardinal::W)]
+                },
+                Fragment {
+                    indices: vec![19, 20, 25],
+                    open_ends: vec![(20, Cardinal::S), (25, Cardinal::S)]
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn main_loop_is_found() {
+        let grid = parse(include_str!("../input_test_5.txt"));
+        let network = grid.analyse_pipes();
+        let main_loop = network
+            .loops
+            .iter()
+            .find(|pipe_loop| pipe_loop.indices[0] == grid.start)
+            .unwrap();
+        assert_eq!(main_loop.indices, grid.loop_indices());
+        assert_eq!(main_loop.enclosed, 8);
+    }
+
+    #[test]
+    fn box_drawing_round_trip() {
+        let grid = parse(include_str!("../input_test_2.txt"));
+        let rendered = grid.render(false);
+        assert_eq!(rendered.lines().next(), Some("..┌┐."));
+        assert_eq!(rendered.lines().nth(2), Some("S┘.└┐"));
+        assert_eq!(parse(&rendered), grid);
+        let grid = parse(include_str!("../input_test_5.txt"));
+        let coloured = grid.render(true);
+        assert!(coloured.contains("\x1b[1;33m┌\x1b[0m"));
+        assert!(coloured.contains("\x1b[42m.\x1b[0m"));
+        assert_eq!(strip_ansi(&coloured), grid.render(false));
+        assert_eq!(parse(&coloured), grid);
+    }
+}
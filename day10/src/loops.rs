@@ -0,0 +1,95 @@
+use crate::grid::Grid;
+use crate::pipe::{Cardinal, Pipe, ALL_DIRECTIONS};
+use std::collections::VecDeque;
+
+/// Closed loop of pipes, walked from the start tile if it is on it, from its first tile otherwise.
+#[derive(PartialEq, Eq, Debug)]
+pub struct PipeLoop {
+    pub indices: Vec<usize>,
+    /// Tile the farthest from the first one, going either way around the loop.
+    pub farthest: usize,
+    pub enclosed: usize,
+}
+
+impl PipeLoop {
+    pub fn length(&self) -> usize {
+        self.indices.len()
+    }
+    pub fn farthest_steps(&self) -> usize {
+        self.indices.len() / 2
+    }
+}
+
+/// Connected pipes that do not close a loop.
+#[derive(PartialEq, Eq, Debug)]
+pub struct Fragment {
+    pub indices: Vec<usize>,
+    /// Pipe sides leading to a tile that does not connect back.
+    pub open_ends: Vec<(usize, Cardinal)>,
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub struct PipeNetwork {
+    pub loops: Vec<PipeLoop>,
+    pub fragments: Vec<Fragment>,
+}
+
+impl Grid {
+    /// Neighbours of `index` connecting back to it, and the sides of `index` left open.
+    fn links(&self, index: usize) -> (Vec<usize>, Vec<Cardinal>) {
+        let pipe = self.elements[index];
+        let mut linked = Vec::new();
+        let mut open = Vec::new();
+        for d in ALL_DIRECTIONS.into_iter().filter(|&d| pipe.has_cardinal(d)) {
+            match self.get_new_index(index, d) {
+                Some(next) if self.elements[next].has_cardinal(d.opposite()) => linked.push(next),
+                _ => open.push(d),
+            }
+        }
+        (linked, open)
+    }
+
+    /// Splits every pipe of the grid into closed loops and dangling fragments.
+    pub fn analyse_pipes(&self) -> PipeNetwork {
+        let mut visited = vec![false; self.elements.len()];
+        let mut loops = Vec::new();
+        let mut fragments = Vec::new();
+        for first in 0..self.elements.len() {
+            if visited[first] || self.elements[first] == Pipe::Ground {
+                continue;
+            }
+            let mut indices = Vec::new();
+            let mut open_ends = Vec::new();
+            let mut queue = VecDeque::from([first]);
+            visited[first] = true;
+            while let Some(index) = queue.pop_front() {
+                indices.push(index);
+                let (linked, open) = self.links(index);
+                open_ends.extend(open.into_iter().map(|d| (index, d)));
+                for next in linked {
+                    if !visited[next] {
+                        visited[next] = true;
+                        queue.push_back(next);
+                    }
+                }
+            }
+            if open_ends.is_empty() {
+                let loop_start = if indices.contains(&self.start) {
+                    self.start
+                } else {
+                    first
+                };
+                let indices = self.follow_loop(loop_start).unwrap();
+                loops.push(PipeLoop {
+                    farthest: indices[indices.len() / 2],
+                    enclosed: self.enclosed_tiles(&indices),
+                    indices,
+                });
+            } else {
+                indices.sort();
+                fragments.push(Fragment { indices, open_ends });
+            }
+        }
+        PipeNetwork { loops, fragments }
+    }
+}
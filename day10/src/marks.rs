@@ -0,0 +1,294 @@
+use crate::grid::{neighbour, Grid};
+use crate::pipe::{Pipe, ALL_DIRECTIONS};
+use std::collections::{HashSet, VecDeque};
+
+use Pipe::*;
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Mark {
+    Blank,
+    Inside,
+    Outside,
+    Loop,
+    Duplicated,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct MarkedPipe {
+    pub pipe: Pipe,
+    pub mark: Mark,
+}
+
+/// Grid where every tile knows if it is on the loop, inside it or outside it.
+pub struct MarkedGrid {
+    pub height: usize,
+    pub width: usize,
+    pub elements: Vec<MarkedPipe>,
+}
+
+/// Connected groups of inside tiles, 4-neighbours being connected.
+pub struct Regions {
+    pub width: usize,
+    /// Region of each tile, `None` for tiles that are not inside the loop.
+    pub labels: Vec<Option<usize>>,
+    /// Number of tiles in each region.
+    pub sizes: Vec<usize>,
+}
+
+impl Regions {
+    pub fn label_at(&self, row: usize, col: usize) -> Option<usize> {
+        if col >= self.width {
+            return None;
+        }
+        self.labels.get(row * self.width + col).copied().flatten()
+    }
+}
+
+impl MarkedGrid {
+    pub fn export_marks(&self) -> String {
+        self.elements
+            .chunks(self.width)
+            .map(|line_elts| {
+                line_elts
+                    .iter()
+                    .map(|e| match e.mark {
+                        Mark::Loop => 'L',
+                        Mark::Outside => 'O',
+                        Mark::Blank => 'B',
+                        Mark::Inside => 'I',
+                        Mark::Duplicated => 'D',
+                    })
+                    .chain(std::iter::once('\n'))
+                    .collect::<String>()
+            })
+            .collect()
+    }
+    pub fn print_grid_marks(&self) {
+        print!("{}", self.export_marks());
+    }
+    pub fn print_grid_elements(&self) {
+        self.elements.chunks(self.width).for_each(|line_elts| {
+            let res: String = line_elts
+                .iter()
+                .map(|e| match e.pipe {
+                    NS => "|",
+                    WE => "-",
+                    NE => "L",
+                    SE => "F",
+                    SW => "7",
+                    NW => "J",
+                    Ground => ".",
+                    Start => "S",
+                })
+                .collect();
+            println!("{res}");
+        })
+    }
+}
+
+impl MarkedGrid {
+    /// Marks the loop through the start tile and turns every other tile into ground.
+    pub fn mark_loop(grid: &Grid) -> MarkedGrid {
+        let mut elements = vec![
+            MarkedPipe {
+                pipe: Ground,
+                mark: Mark::Blank,
+            };
+            grid.elements.len()
+        ];
+        for index in grid.loop_indices() {
+            elements[index] = MarkedPipe {
+                pipe: grid.elements[index],
+                mark: Mark::Loop,
+            };
+        }
+        MarkedGrid {
+            height: grid.height,
+            width: grid.width,
+            elements,
+        }
+    }
+
+    fn get_new_index(&self, old_index: usize, direction: crate::Cardinal) -> Option<usize> {
+        neighbour(old_index, direction, self.width, self.height)
+    }
+
+    /// Doubles the grid so that flood fill can squeeze between adjacent pipes.
+    pub fn extend_grid(&self) -> MarkedGrid {
+        let width = self.width * 2;
+        let height = self.height * 2;
+        let duplicated_rows: Vec<_> = self
+            .elements
+            .chunks(self.width)
+            .flat_map(|chunk| {
+                let iter_new_chunk = chunk.iter().map(|marked_pipe| {
+                    let new_pipe = if marked_pipe.mark == Mark::Loop {
+                        match marked_pipe.pipe {
+                            WE => Ground,
+                            SW => NS,
+                            SE => NS,
+                            NE => Ground,
+                            NW => Ground,
+                            _ => marked_pipe.pipe,
+                        }
+                    } else {
+                        marked_pipe.pipe
+                    };
+                    let new_mark = match new_pipe {
+                        Ground => Mark::Duplicated,
+                        _ => marked_pipe.mark,
+                    };
+                    MarkedPipe {
+                        mark: new_mark,
+                        pipe: new_pipe,
+                    }
+                });
+                chunk.iter().cloned().chain(iter_new_chunk)
+            })
+            .collect();
+        let duplicated_cols = duplicated_rows
+            .into_iter()
+            .flat_map(|marked_pipe| {
+                let new_pipe = if marked_pipe.mark == Mark::Loop {
+                    match marked_pipe.pipe {
+                        NS => Ground,
+                        SE => WE,
+                        NE => WE,
+                        NW => Ground,
+                        SW => Ground,
+                        _ => marked_pipe.pipe,
+                    }
+                } else {
+                    marked_pipe.pipe
+                };
+                let new_mark = match new_pipe {
+                    Ground => Mark::Duplicated,
+                    _ => marked_pipe.mark,
+                };
+                let new_marked_pipe = MarkedPipe {
+                    mark: new_mark,
+                    pipe: new_pipe,
+                };
+                [marked_pipe, new_marked_pipe].into_iter()
+            })
+            .collect();
+        MarkedGrid {
+            height,
+            width,
+            elements: duplicated_cols,
+        }
+    }
+
+    /// Flood fills the first blank region, returns false once there is none left.
+    pub fn try_mark(&mut self) -> bool {
+        let mut visited_index: HashSet<usize> = HashSet::new();
+        let mut to_check_indexes: HashSet<usize> = HashSet::new();
+        let mut mark_variant = Mark::Inside;
+        if let Some(start) = self
+            .elements
+            .iter()
+            .enumerate()
+            .find_map(|(i, marked_pipe)| {
+                if marked_pipe.mark == Mark::Blank && marked_pipe.pipe == Pipe::Ground {
+                    Some(i)
+                } else {
+                    None
+                }
+            })
+        {
+            to_check_indexes.insert(start);
+            while let Some(&to_check_index) = to_check_indexes.iter().next() {
+                visited_index.insert(to_check_index);
+                ALL_DIRECTIONS.iter().for_each(|&d| {
+                    // check if outside grid
+                    if let Some(index) = self.get_new_index(to_check_index, d) {
+                        let marked_pipe = self.elements[index];
+                        // check if we can thread on the next index
+                        if marked_pipe.mark != Mark::Loop && !visited_index.contains(&index) {
+                            to_check_indexes.insert(index);
+                        }
+                    } else {
+                        mark_variant = Mark::Outside;
+                    }
+                });
+                to_check_indexes.remove(&to_check_index);
+            }
+            for elt_index in visited_index {
+                let marked_pipe = self.elements[elt_index];
+                if marked_pipe.mark == Mark::Blank && marked_pipe.pipe == Pipe::Ground {
+                    self.elements[elt_index].mark = mark_variant;
+                }
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Marks every tile of `grid` as on the loop, inside or outside it.
+    pub fn classify(grid: &Grid) -> MarkedGrid {
+        let mut extended_grid = MarkedGrid::mark_loop(grid).extend_grid();
+        while extended_grid.try_mark() {}
+        extended_grid.shrink()
+    }
+
+    /// Drops the rows and columns added by `extend_grid`, back to the original coordinates.
+    pub fn shrink(&self) -> MarkedGrid {
+        let elements = self
+            .elements
+            .chunks(self.width)
+            .step_by(2)
+            .flat_map(|line_elts| line_elts.iter().step_by(2).copied())
+            .collect();
+        MarkedGrid {
+            height: self.height.div_ceil(2),
+            width: self.width.div_ceil(2),
+            elements,
+        }
+    }
+
+    pub fn mark_at(&self, row: usize, col: usize) -> Option<Mark> {
+        if row >= self.height || col >= self.width {
+            return None;
+        }
+        Some(self.elements[row * self.width + col].mark)
+    }
+
+    pub fn inside_regions(&self) -> Regions {
+        let mut labels = vec![None; self.elements.len()];
+        let mut sizes = Vec::new();
+        for first in 0..self.elements.len() {
+            if self.elements[first].mark != Mark::Inside || labels[first].is_some() {
+                continue;
+            }
+            let label = sizes.len();
+            let mut size = 0;
+            let mut queue = VecDeque::from([first]);
+            labels[first] = Some(label);
+            while let Some(index) = queue.pop_front() {
+                size += 1;
+                for d in ALL_DIRECTIONS {
+                    if let Some(next) = self.get_new_index(index, d) {
+                        if self.elements[next].mark == Mark::Inside && labels[next].is_none() {
+                            labels[next] = Some(label);
+                            queue.push_back(next);
+                        }
+                    }
+                }
+            }
+            sizes.push(size);
+        }
+        Regions {
+            width: self.width,
+            labels,
+            sizes,
+        }
+    }
+
+    pub fn count_inside(&self) -> usize {
+        self.elements
+            .iter()
+            .filter(|m_p| m_p.mark == Mark::Inside)
+            .count()
+    }
+}
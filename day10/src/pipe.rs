@@ -0,0 +1,93 @@
+use std::str::FromStr;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub enum Pipe {
+    NS,
+    WE,
+    NE,
+    SE,
+    SW,
+    NW,
+    Ground,
+    Start,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub enum Cardinal {
+    N,
+    E,
+    S,
+    W,
+}
+
+use Cardinal::*;
+use Pipe::*;
+
+pub const ALL_DIRECTIONS: [Cardinal; 4] = [N, E, S, W];
+
+impl Cardinal {
+    pub fn opposite(&self) -> Cardinal {
+        match self {
+            N => S,
+            S => N,
+            E => W,
+            W => E,
+        }
+    }
+    pub fn other(&self) -> [Cardinal; 3] {
+        match self {
+            N => [W, S, E],
+            S => [N, E, W],
+            E => [W, N, S],
+            W => [E, N, S],
+        }
+    }
+}
+
+impl FromStr for Pipe {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "|" | "│" => Ok(NS),
+            "-" | "─" => Ok(WE),
+            "L" | "└" => Ok(NE),
+            "F" | "┌" => Ok(SE),
+            "7" | "┐" => Ok(SW),
+            "J" | "┘" => Ok(NW),
+            "." => Ok(Ground),
+            "S" => Ok(Start),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Pipe {
+    pub fn has_cardinal(&self, cardinal: Cardinal) -> bool {
+        match (self, cardinal) {
+            (&p, N) if p == NS || p == NE || p == NW => true,
+            (&p, S) if p == NS || p == SE || p == SW => true,
+            (&p, E) if p == WE || p == NE || p == SE => true,
+            (&p, W) if p == WE || p == SW || p == NW => true,
+            _ => false,
+        }
+    }
+    /// Pipe connecting two different sides of a tile.
+    pub fn from_cardinals(first: Cardinal, second: Cardinal) -> Option<Pipe> {
+        match (first, second) {
+            (N, S) | (S, N) => Some(NS),
+            (E, S) | (S, E) => Some(SE),
+            (W, S) | (S, W) => Some(SW),
+            (N, E) | (E, N) => Some(NE),
+            (W, N) | (N, W) => Some(NW),
+            (W, E) | (E, W) => Some(WE),
+            _ => None,
+        }
+    }
+    /// Side a walk leaves through after entering the pipe through `entry`.
+    pub fn exit(&self, entry: Cardinal) -> Option<Cardinal> {
+        if !self.has_cardinal(entry) {
+            return None;
+        }
+        entry.other().into_iter().find(|&d| self.has_cardinal(d))
+    }
+}
@@ -0,0 +1,69 @@
+use crate::grid::Grid;
+use crate::marks::{Mark, MarkedGrid};
+use crate::pipe::Pipe;
+
+use Pipe::*;
+
+const RESET: &str = "\x1b[0m";
+const LOOP_COLOUR: &str = "\x1b[1;33m";
+const INSIDE_COLOUR: &str = "\x1b[42m";
+const START_COLOUR: &str = "\x1b[1;31m";
+
+impl Pipe {
+    pub fn to_box_char(&self) -> char {
+        match self {
+            NS => '│',
+            WE => '─',
+            NE => '└',
+            SE => '┌',
+            SW => '┐',
+            NW => '┘',
+            Ground => '.',
+            Start => 'S',
+        }
+    }
+}
+
+/// Removes the ANSI colour codes added by `Grid::render`.
+pub fn strip_ansi(s: &str) -> String {
+    let mut stripped = String::with_capacity(s.len());
+    let mut iter_chars = s.chars();
+    while let Some(c) = iter_chars.next() {
+        if c == '\x1b' {
+            iter_chars.by_ref().find(|&c| c == 'm');
+        } else {
+            stripped.push(c);
+        }
+    }
+    stripped
+}
+
+impl Grid {
+    /// Draws the grid with box-drawing characters, which `Grid::from_str` reads back.
+    /// With `colour`, the main loop and the tiles it encloses are highlighted.
+    pub fn render(&self, colour: bool) -> String {
+        let marked_grid = colour.then(|| MarkedGrid::classify(self));
+        let mut rendered = String::new();
+        for (index, pipe) in self.elements.iter().enumerate() {
+            let c = if index == self.start {
+                'S'
+            } else {
+                pipe.to_box_char()
+            };
+            let highlight = match marked_grid.as_ref().map(|m| m.elements[index].mark) {
+                _ if colour && index == self.start => Some(START_COLOUR),
+                Some(Mark::Loop) => Some(LOOP_COLOUR),
+                Some(Mark::Inside) => Some(INSIDE_COLOUR),
+                _ => None,
+            };
+            match highlight {
+                Some(code) => rendered.push_str(&format!("{}{}{}", code, c, RESET)),
+                None => rendered.push(c),
+            }
+            if (index + 1) % self.width == 0 {
+                rendered.push('\n');
+            }
+        }
+        rendered
+    }
+}
@@ -0,0 +1,561 @@
+use std::io::BufRead;
+use std::ops::Range;
+use std::str::FromStr;
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum Element {
+    Empty,
+    Galaxy,
+}
+
+impl Element {
+    pub fn from_char(c: char) -> Option<Element> {
+        match c {
+            '.' => Some(Element::Empty),
+            '#' => Some(Element::Galaxy),
+            _ => None,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum UniverseError {
+    UnknownChar { row: u64, col: u64, c: char },
+    RaggedRow { row: u64, width: u64 },
+    Io(std::io::ErrorKind),
+}
+
+/// Expanded coordinates past `u64::MAX`.
+#[derive(PartialEq, Eq, Debug)]
+pub struct ExpansionOverflow;
+
+/// Sparse sky map: only the galaxies are stored, in reading order.
+#[derive(PartialEq, Eq, Debug)]
+pub struct Universe {
+    pub height: u64,
+    pub width: u64,
+    pub galaxies: Vec<(u64, u64)>,
+}
+
+impl FromStr for Universe {
+    type Err = UniverseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Universe::from_lines(s.lines().map(Ok))
+    }
+}
+
+impl Universe {
+    /// Galaxies given as (row, col) in a `height` by `width` universe.
+    pub fn from_galaxies(height: u64, width: u64, mut galaxies: Vec<(u64, u64)>) -> Universe {
+        galaxies.sort_unstable();
+        galaxies.dedup();
+        Universe {
+            height,
+            width,
+            galaxies,
+        }
+    }
+
+    /// Parse one line at a time, so the grid itself is never held in memory.
+    pub fn from_reader(reader: impl BufRead) -> Result<Universe, UniverseError> {
+        Universe::from_lines(reader.lines())
+    }
+
+    fn from_lines<S: AsRef<str>>(
+        lines: impl Iterator<Item = std::io::Result<S>>,
+    ) -> Result<Universe, UniverseError> {
+        let mut universe = Universe::from_galaxies(0, 0, Vec::new());
+        for (row, line) in lines.enumerate() {
+            let line = line.map_err(|e| UniverseError::Io(e.kind()))?;
+            let row = row as u64;
+            let mut width = 0;
+            for (col, c) in line.as_ref().chars().enumerate() {
+                let col = col as u64;
+                match Element::from_char(c) {
+                    Some(Element::Galaxy) => universe.galaxies.push((row, col)),
+                    Some(Element::Empty) => (),
+                    None => return Err(UniverseError::UnknownChar { row, col, c }),
+                }
+                width = col + 1;
+            }
+            if row == 0 {
+                universe.width = width;
+            } else if width != universe.width {
+                return Err(UniverseError::RaggedRow { row, width });
+            }
+            universe.height = row + 1;
+        }
+        Ok(universe)
+    }
+
+    /// Distinct rows and distinct columns holding a galaxy, sorted.
+    fn occupied(&self) -> (Vec<u64>, Vec<u64>) {
+        let mut rows: Vec<u64> = self.galaxies.iter().map(|g| g.0).collect();
+        let mut cols: Vec<u64> = self.galaxies.iter().map(|g| g.1).collect();
+        for occupied in [&mut rows, &mut cols] {
+            occupied.sort_unstable();
+            occupied.dedup();
+        }
+        (rows, cols)
+    }
+
+    /// Runs of rows and of columns without any galaxy, as the gaps between occupied ones,
+    /// so their size does not depend on the dimensions.
+    pub fn get_expansion(&self) -> (Vec<Range<u64>>, Vec<Range<u64>>) {
+        let (rows, cols) = self.occupied();
+        let gaps = |occupied: &[u64], len: u64| -> Vec<Range<u64>> {
+            let starts = std::iter::once(0).chain(occupied.iter().map(|&o| o + 1));
+            let ends = occupied.iter().copied().chain(std::iter::once(len));
+            starts
+                .zip(ends)
+                .filter(|(start, end)| start < end)
+                .map(|(start, end)| start..end)
+                .collect()
+        };
+        (gaps(&rows, self.height), gaps(&cols, self.width))
+    }
+
+    /// Galaxy coordinates as (row, col), before expansion.
+    pub fn get_galaxy_coords(&self) -> Vec<(u64, u64)> {
+        self.galaxies.clone()
+    }
+
+    /// Galaxy coordinates once every empty row and column is replaced by `factor` of them.
+    /// A galaxy at `row` has `row - k` empty rows above it, `k` being the occupied rows above,
+    /// so it moves to `k + (row - k) * factor`. A factor of 0 collapses the empty lines.
+    pub fn expanded_galaxy_coords(
+        &self,
+        factor: u64,
+    ) -> Result<Vec<(u64, u64)>, ExpansionOverflow> {
+        let (rows, cols) = self.occupied();
+        let expand = |occupied: &[u64], i: u64| {
+            let occupied_before = occupied.partition_point(|&o| o < i) as u64;
+            (i - occupied_before)
+                .checked_mul(factor)
+                .and_then(|empty| empty.checked_add(occupied_before))
+                .ok_or(ExpansionOverflow)
+        };
+        self.galaxies
+            .iter()
+            .map(|&(row, col)| Ok((expand(&rows, row)?, expand(&cols, col)?)))
+            .collect()
+    }
+
+    pub fn print_universe(&self) {
+        let mut galaxies = self.galaxies.iter().peekable();
+        for row in 0..self.height {
+            let row_str: String = (0..self.width)
+                .map(|col| match galaxies.next_if_eq(&&(row, col)) {
+                    Some(_) => '#',
+                    None => '.',
+                })
+                .collect();
+            println!("{row_str}");
+        }
+    }
+}
+
+/// Sum of `|a - b|` over every pair of values, from the sorted values and their prefix sums.
+fn sum_axis_distances(mut values: Vec<u64>) -> u128 {
+    values.sort_unstable();
+    let mut prefix_sum: u128 = 0;
+    let mut sum = 0;
+    for (i, &value) in values.iter().enumerate() {
+        sum += value as u128 * i as u128 - prefix_sum;
+        prefix_sum += value as u128;
+    }
+    sum
+}
+
+/// Sum of the Manhattan distances between every pair of galaxies, in O(n log n).
+pub fn sum_distances(coordinates_galaxy: &[(u64, u64)]) -> u128 {
+    sum_axis_distances(coordinates_galaxy.iter().map(|g| g.0).collect())
+        + sum_axis_distances(coordinates_galaxy.iter().map(|g| g.1).collect())
+}
+
+pub fn distance(g1: (u64, u64), g2: (u64, u64)) -> u64 {
+    g1.0.abs_diff(g2.0) + g1.1.abs_diff(g2.1)
+}
+
+/// Two galaxies the farthest apart, with their distance.
+/// The Manhattan distance is the largest spread of either `row + col` or `row - col`.
+pub fn farthest_pair(coordinates_galaxy: &[(u64, u64)]) -> Option<(usize, usize, u64)> {
+    if coordinates_galaxy.len() < 2 {
+        return None;
+    }
+    let extremes = |key: &dyn Fn(&(u64, u64)) -> i128| {
+        let indices = 0..coordinates_galaxy.len();
+        let min = indices.clone().min_by_key(|&i| key(&coordinates_galaxy[i]));
+        let max = indices.max_by_key(|&i| key(&coordinates_galaxy[i]));
+        (min.unwrap(), max.unwrap())
+    };
+    let sum = extremes(&|g| g.0 as i128 + g.1 as i128);
+    let difference = extremes(&|g| g.0 as i128 - g.1 as i128);
+    [sum, difference]
+        .into_iter()
+        .map(|(i, j)| (i, j, distance(coordinates_galaxy[i], coordinates_galaxy[j])))
+        .max_by_key(|&(_, _, d)| d)
+}
+
+/// Number of galaxy pairs at each distance, grouped in bins of `bin_width`.
+/// Every pair is visited, so this stays quadratic in the number of galaxies.
+pub fn distance_histogram(coordinates_galaxy: &[(u64, u64)], bin_width: u64) -> Vec<u64> {
+    let mut histogram = Vec::new();
+    for (i, &g1) in coordinates_galaxy.iter().enumerate() {
+        for &g2 in coordinates_galaxy[i + 1..].iter() {
+            let bin = (distance(g1, g2) / bin_width) as usize;
+            if bin >= histogram.len() {
+                histogram.resize(bin + 1, 0);
+            }
+            histogram[bin] += 1;
+        }
+    }
+    histogram
+}
+
+/// Galaxies sorted by row, to look for neighbours without checking every galaxy.
+pub struct GalaxyIndex<'a> {
+    coordinates_galaxy: &'a [(u64, u64)],
+    by_row: Vec<usize>,
+}
+
+impl<'a> GalaxyIndex<'a> {
+    pub fn new(coordinates_galaxy: &'a [(u64, u64)]) -> GalaxyIndex<'a> {
+        let mut by_row: Vec<usize> = (0..coordinates_galaxy.len()).collect();
+        by_row.sort_by_key(|&i| coordinates_galaxy[i]);
+        GalaxyIndex {
+            coordinates_galaxy,
+            by_row,
+        }
+    }
+    /// Closest other galaxy to galaxy `index`, with its distance.
+    pub fn nearest(&self, index: usize) -> Option<(usize, u64)> {
+        let galaxy = self.coordinates_galaxy[index];
+        let position = self
+            .by_row
+            .binary_search_by_key(&galaxy, |&i| self.coordinates_galaxy[i])
+            .unwrap();
+        let mut best: Option<(usize, u64)> = None;
+        // Walk away from the galaxy in row order, until rows alone are farther than the best.
+        let mut walk = |side: &mut dyn Iterator<Item = &usize>| {
+            for &other in side {
+                let other_galaxy = self.coordinates_galaxy[other];
+                if best.is_some_and(|(_, d)| other_galaxy.0.abs_diff(galaxy.0) >= d) {
+                    break;
+                }
+                let d = distance(galaxy, other_galaxy);
+                if best.is_none_or(|(_, best_d)| d < best_d) {
+                    best = Some((other, d));
+                }
+            }
+        };
+        walk(&mut self.by_row[..position].iter().rev());
+        walk(&mut self.by_row[position + 1..].iter());
+        best
+    }
+}
+
+/// Galaxies scattered by a linear congruential generator, for large test universes.
+pub fn synthetic_coords(num_galaxies: usize, size: u64, seed: u64) -> Vec<(u64, u64)> {
+    let mut state = seed;
+    let mut next = move || {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (state >> 33) % size
+    };
+    let mut coords: Vec<(u64, u64)> = (0..num_galaxies).map(|_| (next(), next())).collect();
+    coords.sort_unstable();
+    coords.dedup();
+    coords
+}
+
+/// Where the sky map comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// The puzzle text.
+    Text,
+    /// A file streamed line by line.
+    File(String),
+    /// Scattered galaxies in a 2^40 by 2^40 universe.
+    Synthetic(usize),
+}
+
+pub fn load(input: &str, source: &Source) -> Result<Universe, UniverseError> {
+    match source {
+        Source::Text => Universe::from_str(input),
+        Source::File(path) => {
+            let file = std::fs::File::open(path).map_err(|e| UniverseError::Io(e.kind()))?;
+            Universe::from_reader(std::io::BufReader::new(file))
+        }
+        Source::Synthetic(num_galaxies) => Ok(Universe::from_galaxies(
+            1 << 40,
+            1 << 40,
+            synthetic_coords(*num_galaxies, 1 << 40, 42),
+        )),
+    }
+}
+
+/// `--stats` skips the quadratic distance histogram above `HISTOGRAM_MAX_GALAXIES` galaxies,
+/// and a universe read with `--input` is streamed from the file and not printed.
+pub const USAGE: &str = "Usage: [FACTOR] [--input PATH] [--synthetic NUM_GALAXIES] [--stats]";
+
+/// Command line of both parts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Args {
+    pub factor: u64,
+    pub source: Source,
+    pub stats: bool,
+}
+
+/// Parses the arguments shared by both parts, which only differ in the default `factor`.
+pub fn parse_args(args: impl IntoIterator<Item = String>, factor: u64) -> Result<Args, String> {
+    let mut parsed = Args {
+        factor,
+        source: Source::Text,
+        stats: false,
+    };
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--synthetic" => {
+                let num_galaxies = args.next().unwrap_or_default();
+                let num_galaxies = num_galaxies
+                    .parse()
+                    .map_err(|_| format!("--synthetic expects a number, got {num_galaxies:?}"))?;
+                parsed.source = Source::Synthetic(num_galaxies);
+            }
+            "--input" => {
+                let path = args.next().ok_or("--input expects a path")?;
+                parsed.source = Source::File(path);
+            }
+            "--stats" => parsed.stats = true,
+            _ if arg.starts_with("--") => return Err(format!("unknown flag {arg}")),
+            _ => {
+                parsed.factor = arg
+                    .parse()
+                    .map_err(|_| format!("FACTOR expects a number, got {arg:?}"))?
+            }
+        }
+    }
+    Ok(parsed)
+}
+
+/// Above this many galaxies, `stats_report` skips the quadratic distance histogram.
+pub const HISTOGRAM_MAX_GALAXIES: usize = 10_000;
+
+/// Farthest and closest pairs, then a 20-bin histogram of the pair distances
+/// for universes of at most `HISTOGRAM_MAX_GALAXIES` galaxies.
+pub fn stats_report(coordinates_galaxy: &[(u64, u64)]) -> String {
+    let mut report = String::new();
+    let farthest = farthest_pair(coordinates_galaxy);
+    if let Some((i, j, d)) = farthest {
+        report += &format!(
+            "farthest pair: {:?} and {:?}, {} apart\n",
+            coordinates_galaxy[i], coordinates_galaxy[j], d
+        );
+    }
+    let galaxy_index = GalaxyIndex::new(coordinates_galaxy);
+    let closest = (0..coordinates_galaxy.len())
+        .filter_map(|i| galaxy_index.nearest(i).map(|(j, d)| (i, j, d)))
+        .min_by_key(|&(_, _, d)| d);
+    if let Some((i, j, d)) = closest {
+        report += &format!(
+            "closest pair: {:?} and {:?}, {} apart\n",
+            coordinates_galaxy[i], coordinates_galaxy[j], d
+        );
+    }
+    if coordinates_galaxy.len() > HISTOGRAM_MAX_GALAXIES {
+        report += &format!(
+            "histogram skipped: more than {} galaxies\n",
+            HISTOGRAM_MAX_GALAXIES
+        );
+    } else if let Some((_, _, max_distance)) = farthest {
+        let bin_width = (max_distance / 20).max(1);
+        for (bin, count) in distance_histogram(coordinates_galaxy, bin_width)
+            .iter()
+            .enumerate()
+        {
+            report += &format!("{:>16}: {}\n", bin as u64 * bin_width, count);
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+
+    fn brute_force_pairs(coords: &[(u64, u64)]) -> Vec<(usize, usize, u64)> {
+        (0..coords.len())
+            .combinations(2)
+            .map(|pair| (pair[0], pair[1], distance(coords[pair[0]], coords[pair[1]])))
+            .collect()
+    }
+
+    fn sum_for_factor(factor: u64) -> u128 {
+        let universe = Universe::from_str(include_str!("../input_test.txt")).unwrap();
+        sum_distances(&universe.expanded_galaxy_coords(factor).unwrap())
+    }
+
+    #[test]
+    fn expansion_factors() {
+        assert_eq!(sum_for_factor(1), 292);
+        assert_eq!(sum_for_factor(2), 374);
+        assert_eq!(sum_for_factor(10), 1030);
+        assert_eq!(sum_for_factor(100), 8410);
+    }
+
+    #[test]
+    fn collapse_and_overflow() {
+        // Still linear in the factor: 292 + 82 * (0 - 1).
+        assert_eq!(sum_for_factor(0), 210);
+        let universe = Universe::from_str(".#.\n...\n..#").unwrap();
+        assert_eq!(universe.expanded_galaxy_coords(0), Ok(vec![(0, 0), (1, 1)]));
+        let size = 1 << 40;
+        let universe = Universe::from_galaxies(size, size, vec![(0, 0), (size - 1, size - 1)]);
+        assert_eq!(
+            universe.expanded_galaxy_coords(1_000_000_000_000),
+            Err(ExpansionOverflow)
+        );
+        assert!(universe.expanded_galaxy_coords(1_000_000).is_ok());
+        let (empty_rows, empty_cols) = universe.get_expansion();
+        assert_eq!(
+            empty_rows,
+            vec![Range {
+                start: 1,
+                end: size - 1
+            }]
+        );
+        assert_eq!(empty_cols, empty_rows);
+    }
+
+    #[test]
+    fn huge_factor() {
+        // Sums grow linearly with the factor: 292 + 82 * (factor - 1).
+        let factor = 1_000_000_000_000;
+        assert_eq!(sum_for_factor(factor), 292 + 82 * (factor as u128 - 1));
+    }
+
+    #[test]
+    fn queries_match_brute_force() {
+        let coords = synthetic_coords(300, 1000, 7);
+        let pairs = brute_force_pairs(&coords);
+        let brute_sum: u128 = pairs.iter().map(|&(_, _, d)| d as u128).sum();
+        assert_eq!(sum_distances(&coords), brute_sum);
+        let brute_max = pairs.iter().map(|&(_, _, d)| d).max().unwrap();
+        assert_eq!(farthest_pair(&coords).unwrap().2, brute_max);
+        let galaxy_index = GalaxyIndex::new(&coords);
+        for i in 0..coords.len() {
+            let brute_nearest = pairs
+                .iter()
+                .filter(|&&(a, b, _)| a == i || b == i)
+                .map(|&(_, _, d)| d)
+                .min();
+            assert_eq!(galaxy_index.nearest(i).map(|(_, d)| d), brute_nearest);
+        }
+        let histogram = distance_histogram(&coords, 100);
+        assert_eq!(histogram.iter().sum::<u64>(), pairs.len() as u64);
+        let short_pairs = pairs.iter().filter(|&&(_, _, d)| d < 100).count();
+        assert_eq!(histogram[0], short_pairs as u64);
+    }
+
+    #[test]
+    fn single_galaxy() {
+        let coords = vec![(3, 4)];
+        assert_eq!(sum_distances(&coords), 0);
+        assert_eq!(farthest_pair(&coords), None);
+        assert_eq!(GalaxyIndex::new(&coords).nearest(0), None);
+    }
+
+    #[test]
+    fn streaming_parse() {
+        let input = include_str!("../input_test.txt");
+        let universe = Universe::from_reader(input.as_bytes()).unwrap();
+        assert_eq!(universe, Universe::from_str(input).unwrap());
+        assert_eq!((universe.height, universe.width), (10, 10));
+        assert_eq!(universe.galaxies.len(), 9);
+        assert_eq!(universe.galaxies[0], (0, 3));
+        assert_eq!(
+            universe.get_expansion(),
+            (vec![3..4, 7..8], vec![2..3, 5..6, 8..9])
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            Universe::from_str("..#\n.x."),
+            Err(UniverseError::UnknownChar {
+                row: 1,
+                col: 1,
+                c: 'x'
+            })
+        );
+        assert_eq!(
+            Universe::from_str("..#\n#."),
+            Err(UniverseError::RaggedRow { row: 1, width: 2 })
+        );
+    }
+
+    #[test]
+    fn huge_sparse_universe() {
+        // Empty rows and columns are never listed, only counted from the occupied ones.
+        let size = 1 << 40;
+        let universe = Universe::from_galaxies(size, size, vec![(size - 1, 0), (0, size - 1)]);
+        assert_eq!(
+            universe.expanded_galaxy_coords(3),
+            Ok(vec![(0, 3 * (size - 2) + 1), (3 * (size - 2) + 1, 0)])
+        );
+    }
+
+    #[test]
+    fn command_line() {
+        let args = |args: &[&str]| parse_args(args.iter().map(|arg| arg.to_string()), 2);
+        assert_eq!(
+            args(&["10", "--synthetic", "50", "--stats"]),
+            Ok(Args {
+                factor: 10,
+                source: Source::Synthetic(50),
+                stats: true
+            })
+        );
+        assert_eq!(
+            args(&["--input", "sky.txt"]).map(|parsed| (parsed.factor, parsed.source)),
+            Ok((2, Source::File("sky.txt".to_string())))
+        );
+        assert_eq!(args(&["--stat"]), Err("unknown flag --stat".to_string()));
+        assert!(args(&["--input"]).is_err());
+        assert!(args(&["ten"]).is_err());
+    }
+
+    #[test]
+    fn sources_and_stats() {
+        let universe = load(include_str!("../input_test.txt"), &Source::Text).unwrap();
+        assert_eq!(universe.galaxies.len(), 9);
+        let synthetic = load("", &Source::Synthetic(50)).unwrap();
+        assert_eq!((synthetic.height, synthetic.galaxies.len()), (1 << 40, 50));
+        assert!(matches!(
+            load("", &Source::File("missing.txt".to_string())),
+            Err(UniverseError::Io(_))
+        ));
+        let report = stats_report(&universe.expanded_galaxy_coords(2).unwrap());
+        let mut lines = report.lines();
+        assert_eq!(
+            lines.next(),
+            Some("farthest pair: (1, 9) and (11, 0), 19 apart")
+        );
+        assert!(lines.next().unwrap().starts_with("closest pair: "));
+        let total: u64 = lines
+            .map(|line| line.split(": ").nth(1).unwrap().parse::<u64>().unwrap())
+            .sum();
+        assert_eq!(total, 36);
+        let crowded = synthetic_coords(HISTOGRAM_MAX_GALAXIES + 1, 1 << 40, 42);
+        let report = stats_report(&crowded);
+        assert_eq!(
+            report.lines().last(),
+            Some("histogram skipped: more than 10000 galaxies")
+        );
+    }
+}
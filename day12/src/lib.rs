@@ -0,0 +1,473 @@
+use num::BigUint;
+use std::str::FromStr;
+
+pub mod nonogram;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Condition {
+    Operational,
+    Damaged,
+    Unknown,
+}
+
+use Condition::*;
+
+impl Condition {
+    pub fn from_char(c: char) -> Option<Condition> {
+        match c {
+            '.' => Some(Operational),
+            '#' => Some(Damaged),
+            '?' => Some(Unknown),
+            _ => None,
+        }
+    }
+    pub fn to_char(self) -> char {
+        match self {
+            Operational => '.',
+            Damaged => '#',
+            Unknown => '?',
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum RecordError {
+    UnknownChar(char),
+    MissingBlocks,
+    Block(String),
+}
+
+/// One line of the input: the spring conditions and the sizes of the damaged blocks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub data: Vec<Condition>,
+    pub blocks: Vec<usize>,
+}
+
+impl FromStr for Record {
+    type Err = RecordError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut iter_whitespace = s.split_whitespace();
+        let data = iter_whitespace
+            .next()
+            .unwrap_or("")
+            .chars()
+            .map(|c| Condition::from_char(c).ok_or(RecordError::UnknownChar(c)))
+            .collect::<Result<_, _>>()?;
+        let damaged_blocks = iter_whitespace.next().ok_or(RecordError::MissingBlocks)?;
+        let blocks = damaged_blocks
+            .split(',')
+            .map(|b| b.parse().map_err(|_| RecordError::Block(b.to_string())))
+            .collect::<Result<_, _>>()?;
+        Ok(Record { data, blocks })
+    }
+}
+
+/// How a record is repeated before counting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unfolding {
+    pub factor: usize,
+    /// Spring inserted between two copies of the conditions, if any.
+    pub separator: Option<Condition>,
+    /// Whether the block list is repeated along with the conditions.
+    pub repeat_blocks: bool,
+}
+
+impl Unfolding {
+    /// Five copies joined by an `Unknown` spring, as in part 2.
+    pub fn puzzle() -> Unfolding {
+        Unfolding::new(5)
+    }
+    pub fn new(factor: usize) -> Unfolding {
+        Unfolding {
+            factor,
+            separator: Some(Unknown),
+            repeat_blocks: true,
+        }
+    }
+}
+
+impl Record {
+    pub fn unfold(&self, unfolding: &Unfolding) -> Record {
+        let mut data = Vec::new();
+        for i in 0..unfolding.factor {
+            if i != 0 {
+                data.extend(unfolding.separator);
+            }
+            data.extend(self.data.iter().copied());
+        }
+        let blocks = if unfolding.repeat_blocks {
+            self.blocks.repeat(unfolding.factor)
+        } else {
+            self.blocks.clone()
+        };
+        Record { data, blocks }
+    }
+
+    pub fn count_arrangements(&self) -> BigUint {
+        count_arrangements(&self.data, &self.blocks)
+    }
+
+    pub fn arrangements(&self) -> Arrangements<'_> {
+        Arrangements::new(&self.data, &self.blocks)
+    }
+
+    /// The record with every `Unknown` forced by the blocks filled in, or `None` if it has
+    /// no arrangement at all.
+    pub fn deduce(&self) -> Option<Record> {
+        let mut data = self.data.clone();
+        for (i, condition) in forced_cells(&self.data, &self.blocks)? {
+            data[i] = condition;
+        }
+        Some(Record {
+            data,
+            blocks: self.blocks.clone(),
+        })
+    }
+}
+
+pub fn conditions_to_string(data: &[Condition]) -> String {
+    data.iter().map(|c| c.to_char()).collect()
+}
+
+/// Lazy enumeration of the concrete rows matching some conditions and blocks, by backtracking
+/// over the start of each block. Meant for small rows: the count can be exponential.
+pub struct Arrangements<'a> {
+    data: &'a [Condition],
+    blocks: &'a [usize],
+    starts: Vec<usize>,
+    /// Smallest start to try for the next block to place.
+    next_start: usize,
+    done: bool,
+}
+
+impl<'a> Arrangements<'a> {
+    pub fn new(data: &'a [Condition], blocks: &'a [usize]) -> Arrangements<'a> {
+        Arrangements {
+            data,
+            blocks,
+            starts: Vec::new(),
+            next_start: 0,
+            done: false,
+        }
+    }
+
+    fn fits(&self, start: usize, size: usize) -> bool {
+        start + size <= self.data.len()
+            && self.data[start..start + size]
+                .iter()
+                .all(|&c| c != Operational)
+            && self.data.get(start + size) != Some(&Damaged)
+    }
+
+    /// Give up on the last placed block and try it one spring further.
+    fn backtrack(&mut self) {
+        match self.starts.pop() {
+            Some(start) => self.next_start = start + 1,
+            None => self.done = true,
+        }
+    }
+
+    fn build(&self) -> Vec<Condition> {
+        let mut row = vec![Operational; self.data.len()];
+        for (&start, &size) in self.starts.iter().zip(self.blocks) {
+            row[start..start + size].fill(Damaged);
+        }
+        row
+    }
+}
+
+impl Iterator for Arrangements<'_> {
+    type Item = Vec<Condition>;
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            let block = self.starts.len();
+            let free_from = match block {
+                0 => 0,
+                _ => self.starts[block - 1] + self.blocks[block - 1] + 1,
+            };
+            if block == self.blocks.len() {
+                let rest = self.data.get(free_from..).unwrap_or(&[]);
+                let row = rest.iter().all(|&c| c != Damaged).then(|| self.build());
+                self.backtrack();
+                if row.is_some() {
+                    return row;
+                }
+                continue;
+            }
+            let size = self.blocks[block];
+            // Springs skipped before the block are operational, so a damaged one stops the search.
+            // Those before `next_start` were already checked when the block was placed there.
+            let start = (self.next_start..=self.data.len())
+                .take_while(|&s| s == free_from || self.data[s - 1] != Damaged)
+                .find(|&s| self.fits(s, size));
+            match start {
+                Some(start) => {
+                    self.starts.push(start);
+                    self.next_start = start + size + 1;
+                }
+                None => self.backtrack(),
+            }
+        }
+        None
+    }
+}
+
+/// `Unknown` cells that take the same state in every arrangement, with that state.
+/// `None` if the conditions cannot match the blocks.
+pub fn forced_cells(data: &[Condition], blocks: &[usize]) -> Option<Vec<(usize, Condition)>> {
+    let total = count_arrangements(data, blocks);
+    if total == BigUint::ZERO {
+        return None;
+    }
+    let mut forced = Vec::new();
+    let mut trial = data.to_vec();
+    for i in 0..data.len() {
+        if data[i] != Unknown {
+            continue;
+        }
+        trial[i] = Operational;
+        let operational = count_arrangements(&trial, blocks);
+        trial[i] = Unknown;
+        if operational == BigUint::ZERO {
+            forced.push((i, Damaged));
+        } else if operational == total {
+            forced.push((i, Operational));
+        }
+    }
+    Some(forced)
+}
+
+/// Number of ways to replace every `Unknown` so that the damaged springs form exactly `blocks`.
+///
+/// The table holds, for the springs read so far, the number of arrangements having completed
+/// `block` blocks and being `run` springs into the next one. Each spring moves every state
+/// forward once, so the whole count is O(len * blocks * longest block).
+/// Only the blocks that can still be reached and still fit in the remaining springs are
+/// visited, and counts are arbitrary precision so heavily unfolded records cannot overflow.
+pub fn count_arrangements(data: &[Condition], blocks: &[usize]) -> BigUint {
+    let longest = blocks.iter().copied().max().unwrap_or(0);
+    let width = longest + 1;
+    // Springs needed before completing `block` blocks, and to place the blocks from `block` on.
+    let mut head = vec![0; blocks.len() + 1];
+    let mut tail = vec![0; blocks.len() + 1];
+    for (i, &size) in blocks.iter().enumerate() {
+        head[i + 1] = head[i] + size + usize::from(i != 0);
+    }
+    for (i, &size) in blocks.iter().enumerate().rev() {
+        tail[i] = tail[i + 1] + size + usize::from(i + 1 != blocks.len());
+    }
+    let mut table = vec![BigUint::ZERO; (blocks.len() + 1) * width];
+    let mut next = table.clone();
+    table[0] = BigUint::from(1u8);
+    let (mut low, mut high) = (0, 0);
+    for (position, &condition) in data.iter().enumerate() {
+        let remaining = data.len() - position;
+        while low < blocks.len() && tail[low] > remaining + longest {
+            low += 1;
+        }
+        while high < blocks.len() && head[high + 1] <= position {
+            high += 1;
+        }
+        if low > high {
+            return BigUint::ZERO;
+        }
+        for cell in &mut next[low * width..((high + 2).min(blocks.len() + 1)) * width] {
+            *cell = BigUint::ZERO;
+        }
+        for block in low..=high {
+            for run in 0..width {
+                let count = &table[block * width + run];
+                if count == &BigUint::ZERO {
+                    continue;
+                }
+                if condition != Damaged {
+                    // Operational: closes the current run, which must then match its block.
+                    if run == 0 {
+                        next[block * width] += count;
+                    } else if run == blocks[block] {
+                        next[(block + 1) * width] += count;
+                    }
+                }
+                if condition != Operational && block < blocks.len() && run < blocks[block] {
+                    next[block * width + run + 1] += count;
+                }
+            }
+        }
+        std::mem::swap(&mut table, &mut next);
+    }
+    let finished = &table[blocks.len() * width];
+    match blocks.last() {
+        Some(&last) => finished + &table[(blocks.len() - 1) * width + last],
+        None => finished.clone(),
+    }
+}
+
+pub fn parse_records(input: &str) -> Vec<Record> {
+    input
+        .lines()
+        .map(|line| Record::from_str(line).unwrap())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(line: &str) -> BigUint {
+        Record::from_str(line).unwrap().count_arrangements()
+    }
+
+    #[test]
+    fn test_basic_combination() {
+        // Inner gaps only: both ends are damaged.
+        assert_eq!(count("#????# 1,1,1"), BigUint::from(2u32));
+        assert_eq!(count("#?????# 1,1,1"), BigUint::from(3u32));
+        assert_eq!(count("#???????# 1,1,1,1"), BigUint::from(6u32));
+    }
+
+    #[test]
+    fn test_opt_combination() {
+        assert_eq!(count("?????? 1,1,1"), BigUint::from(4u32));
+        assert_eq!(count("#????? 1,1,1"), BigUint::from(3u32));
+        assert_eq!(count("??????? 1,1,1"), BigUint::from(10u32));
+    }
+
+    #[test]
+    fn example_rows() {
+        let counts: Vec<BigUint> = parse_records(include_str!("../input_test.txt"))
+            .iter()
+            .map(Record::count_arrangements)
+            .collect();
+        assert_eq!(counts, [1u32, 4, 1, 1, 4, 10].map(BigUint::from));
+        let unfolded: Vec<BigUint> = parse_records(include_str!("../input_test.txt"))
+            .iter()
+            .map(|record| record.unfold(&Unfolding::puzzle()).count_arrangements())
+            .collect();
+        assert_eq!(
+            unfolded,
+            [1u32, 16384, 1, 16, 2500, 506250].map(BigUint::from)
+        );
+    }
+
+    #[test]
+    fn edge_cases() {
+        assert_eq!(count(". 1"), BigUint::from(0u32));
+        assert_eq!(count("### 3"), BigUint::from(1u32));
+        assert_eq!(count("#### 3"), BigUint::from(0u32));
+        assert_eq!(count("??? 4"), BigUint::from(0u32));
+    }
+
+    #[test]
+    fn unfolding_options() {
+        let record = Record::from_str("?#? 1").unwrap();
+        let unfolding = Unfolding {
+            factor: 3,
+            separator: None,
+            repeat_blocks: false,
+        };
+        assert_eq!(
+            record.unfold(&unfolding),
+            Record::from_str("?#??#??#? 1").unwrap()
+        );
+        let unfolding = Unfolding {
+            separator: Some(Operational),
+            ..Unfolding::new(2)
+        };
+        let unfolded = record.unfold(&unfolding);
+        assert_eq!(unfolded, Record::from_str("?#?.?#? 1,1").unwrap());
+        assert_eq!(unfolded.count_arrangements(), BigUint::from(1u32));
+    }
+
+    #[test]
+    fn large_factor_does_not_overflow() {
+        // `?` repeated 50 times around `?` separators is 199 unknowns holding 50 single springs,
+        // which can be placed in C(150, 50) ways.
+        let record = Record::from_str("??? 1")
+            .unwrap()
+            .unfold(&Unfolding::new(50));
+        let binomial = (1..=50u32).fold(BigUint::from(1u32), |acc, i| {
+            acc * BigUint::from(100 + i) / BigUint::from(i)
+        });
+        assert!(binomial > BigUint::from(u64::MAX));
+        assert_eq!(record.count_arrangements(), binomial);
+    }
+
+    #[test]
+    fn arrangements_match_count() {
+        for record in parse_records(include_str!("../input.txt")).iter() {
+            let arrangements: Vec<Vec<Condition>> = record.arrangements().collect();
+            assert_eq!(
+                BigUint::from(arrangements.len()),
+                record.count_arrangements()
+            );
+            for row in arrangements.iter() {
+                assert_eq!(count_arrangements(row, &record.blocks), BigUint::from(1u32));
+                assert!(row
+                    .iter()
+                    .zip(record.data.iter())
+                    .all(|(r, d)| d == &Unknown || r == d));
+            }
+        }
+    }
+
+    #[test]
+    fn arrangements_are_lazy() {
+        let record = Record::from_str("???.### 1,1,3").unwrap();
+        let rows: Vec<String> = record
+            .arrangements()
+            .map(|row| conditions_to_string(&row))
+            .collect();
+        assert_eq!(rows, vec!["#.#.###"]);
+        let huge = Record::from_str("??? 1")
+            .unwrap()
+            .unfold(&Unfolding::new(50));
+        let first = huge.arrangements().next().unwrap();
+        assert_eq!(conditions_to_string(&first[..8]), "#.#.#.#.");
+    }
+
+    #[test]
+    fn deduction() {
+        let deduce = |line: &str| {
+            Record::from_str(line)
+                .unwrap()
+                .deduce()
+                .map(|record| conditions_to_string(&record.data))
+        };
+        assert_eq!(deduce("???.### 1,1,3").as_deref(), Some("#.#.###"));
+        assert_eq!(
+            deduce("?###???????? 3,2,1").as_deref(),
+            Some(".###.???????")
+        );
+        assert_eq!(deduce("??????? 5").as_deref(), Some("??###??"));
+        assert_eq!(deduce("#?# 3"), Some("###".to_string()));
+        assert_eq!(deduce("#.# 3"), None);
+    }
+
+    #[test]
+    fn nonogram_heart() {
+        use nonogram::Nonogram;
+        let nonogram = Nonogram::from_str(include_str!("../nonogram_test.txt")).unwrap();
+        let mut picture = nonogram.blank();
+        nonogram.propagate(&mut picture).unwrap();
+        // Line deduction alone is enough for this one.
+        assert!(picture.is_complete());
+        assert!(nonogram.check(&picture));
+        assert_eq!(picture.to_string().lines().nth(1), Some(".####.####"));
+    }
+
+    #[test]
+    fn nonogram_backtracking() {
+        use nonogram::Nonogram;
+        // Both diagonals fit, so deduction gets stuck and a guess is needed.
+        let nonogram = Nonogram::from_str("rows:\n1\n1\ncolumns:\n1\n1\n").unwrap();
+        let mut picture = nonogram.blank();
+        nonogram.propagate(&mut picture).unwrap();
+        assert!(!picture.is_complete());
+        let solved = nonogram.solve().unwrap();
+        assert!(nonogram.check(&solved));
+        assert_eq!(solved.to_string(), "#.\n.#\n");
+        let impossible = Nonogram::from_str("rows:\n2\n0\ncolumns:\n0\n1\n").unwrap();
+        assert_eq!(impossible.solve(), None);
+    }
+}
@@ -0,0 +1,154 @@
+use crate::{conditions_to_string, count_arrangements, forced_cells, Condition};
+use num::BigUint;
+use std::fmt;
+use std::str::FromStr;
+
+use Condition::*;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum NonogramError {
+    MissingSection(&'static str),
+    Clue(String),
+}
+
+/// Row and column clues. A cell is `Damaged` when filled and `Operational` when blank,
+/// so each line is a day12 record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Nonogram {
+    pub rows: Vec<Vec<usize>>,
+    pub columns: Vec<Vec<usize>>,
+}
+
+/// Clues are given one line at a time under `rows:` then `columns:`, as comma separated
+/// block sizes, with `0` for an empty line.
+impl FromStr for Nonogram {
+    type Err = NonogramError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_clue = |line: &str| -> Result<Vec<usize>, NonogramError> {
+            line.split(',')
+                .map(|b| {
+                    b.trim()
+                        .parse()
+                        .map_err(|_| NonogramError::Clue(line.to_string()))
+                })
+                .filter(|b| b != &Ok(0))
+                .collect()
+        };
+        let (rows, columns) = s
+            .split_once("columns:")
+            .ok_or(NonogramError::MissingSection("columns:"))?;
+        let rows = rows
+            .trim()
+            .strip_prefix("rows:")
+            .ok_or(NonogramError::MissingSection("rows:"))?;
+        let parse_section = |section: &str| -> Result<Vec<Vec<usize>>, NonogramError> {
+            section
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(parse_clue)
+                .collect()
+        };
+        Ok(Nonogram {
+            rows: parse_section(rows)?,
+            columns: parse_section(columns)?,
+        })
+    }
+}
+
+/// Cells of a nonogram, `Unknown` until deduced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Picture {
+    pub cells: Vec<Vec<Condition>>,
+}
+
+impl Picture {
+    fn column(&self, col: usize) -> Vec<Condition> {
+        self.cells.iter().map(|row| row[col]).collect()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.cells.iter().flatten().all(|&c| c != Unknown)
+    }
+}
+
+impl fmt::Display for Picture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.cells.iter() {
+            writeln!(f, "{}", conditions_to_string(row))?;
+        }
+        Ok(())
+    }
+}
+
+/// The picture could not match the clues.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Contradiction;
+
+impl Nonogram {
+    pub fn blank(&self) -> Picture {
+        Picture {
+            cells: vec![vec![Unknown; self.columns.len()]; self.rows.len()],
+        }
+    }
+
+    /// Apply the line deduction to every row and column until nothing changes.
+    pub fn propagate(&self, picture: &mut Picture) -> Result<(), Contradiction> {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (row, clue) in self.rows.iter().enumerate() {
+                for (col, condition) in
+                    forced_cells(&picture.cells[row], clue).ok_or(Contradiction)?
+                {
+                    picture.cells[row][col] = condition;
+                    changed = true;
+                }
+            }
+            for (col, clue) in self.columns.iter().enumerate() {
+                for (row, condition) in
+                    forced_cells(&picture.column(col), clue).ok_or(Contradiction)?
+                {
+                    picture.cells[row][col] = condition;
+                    changed = true;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Deduce as far as possible, then guess the first unknown cell and recurse on each choice.
+    pub fn solve(&self) -> Option<Picture> {
+        self.solve_from(self.blank())
+    }
+
+    fn solve_from(&self, mut picture: Picture) -> Option<Picture> {
+        self.propagate(&mut picture).ok()?;
+        let Some((row, col)) = (0..self.rows.len())
+            .flat_map(|row| (0..self.columns.len()).map(move |col| (row, col)))
+            .find(|&(row, col)| picture.cells[row][col] == Unknown)
+        else {
+            return Some(picture);
+        };
+        [Damaged, Operational].into_iter().find_map(|guess| {
+            let mut attempt = picture.clone();
+            attempt.cells[row][col] = guess;
+            self.solve_from(attempt)
+        })
+    }
+
+    /// Whether a complete picture satisfies every clue.
+    pub fn check(&self, picture: &Picture) -> bool {
+        let one = BigUint::from(1u8);
+        picture.is_complete()
+            && self
+                .rows
+                .iter()
+                .enumerate()
+                .all(|(row, clue)| count_arrangements(&picture.cells[row], clue) == one)
+            && self
+                .columns
+                .iter()
+                .enumerate()
+                .all(|(col, clue)| count_arrangements(&picture.column(col), clue) == one)
+    }
+}
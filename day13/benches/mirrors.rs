@@ -0,0 +1,26 @@
+use day13::Pattern;
+
+fn main() {
+    // Run registered benchmarks.
+    divan::main();
+}
+
+#[divan::bench(args = [64, 256, 1024, 4096])]
+fn find_mirrors(bencher: divan::Bencher, size: usize) {
+    let pattern = Pattern::generate(size, size, size / 3, 42);
+    bencher.bench(|| divan::black_box(&pattern).find_mirrors(0));
+}
+
+#[divan::bench(args = [64, 256, 1024, 4096])]
+fn find_mirrors_one_smudge(bencher: divan::Bencher, size: usize) {
+    let pattern = Pattern::generate(size, size, size / 3, 42);
+    bencher.bench(|| divan::black_box(&pattern).find_mirrors(1));
+}
+
+#[divan::bench]
+fn input() -> usize {
+    day13::parse_patterns(include_str!("../input.txt"))
+        .iter()
+        .map(|pattern| pattern.get_score(1))
+        .sum()
+}
@@ -0,0 +1,639 @@
+use std::ops::Range;
+use std::str::FromStr;
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Terrain {
+    Rock,
+    Ash,
+}
+
+/// Lines of bits packed into `u64` words, `words` of them per line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lines {
+    words: usize,
+    bits: Vec<u64>,
+}
+
+impl Lines {
+    fn new(num_lines: usize, length: usize) -> Lines {
+        let words = length.div_ceil(64);
+        Lines {
+            words,
+            bits: vec![0; num_lines * words],
+        }
+    }
+
+    fn line(&self, i: usize) -> &[u64] {
+        &self.bits[i * self.words..(i + 1) * self.words]
+    }
+
+    fn set(&mut self, line: usize, i: usize) {
+        self.bits[line * self.words + i / 64] |= 1 << (i % 64);
+    }
+
+    fn get(&self, line: usize, i: usize) -> bool {
+        self.bits[line * self.words + i / 64] >> (i % 64) & 1 == 1
+    }
+
+    /// Number of positions where two lines differ.
+    fn differences(&self, l1: usize, l2: usize) -> usize {
+        self.line(l1)
+            .iter()
+            .zip(self.line(l2))
+            .map(|(w1, w2)| (w1 ^ w2).count_ones() as usize)
+            .sum()
+    }
+
+    /// Positions where two lines differ, in increasing order.
+    fn difference_positions(&self, l1: usize, l2: usize) -> impl Iterator<Item = usize> + '_ {
+        self.line(l1)
+            .iter()
+            .zip(self.line(l2))
+            .enumerate()
+            .flat_map(|(word_i, (w1, w2))| {
+                let mut diff = w1 ^ w2;
+                std::iter::from_fn(move || {
+                    (diff != 0).then(|| {
+                        let bit = diff.trailing_zeros() as usize;
+                        diff &= diff - 1;
+                        word_i * 64 + bit
+                    })
+                })
+            })
+    }
+}
+
+/// Rocks are set bits, packed both by row and by column so that either mirror direction
+/// compares whole lines.
+#[derive(Debug)]
+pub struct Pattern {
+    pub height: usize,
+    pub width: usize,
+    rows: Lines,
+    columns: Lines,
+}
+
+impl FromStr for Pattern {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let width = s.split_terminator('\n').next().ok_or(())?.chars().count();
+        let height = s.split_terminator('\n').count();
+        let grid = s
+            .chars()
+            .filter(|&c| c != '\n')
+            .map(|c| match c {
+                '#' => Ok(Terrain::Rock),
+                '.' => Ok(Terrain::Ash),
+                _ => Err(()),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if grid.len() != width * height {
+            return Err(());
+        }
+        Ok(Pattern::from_cells(height, width, |row, col| {
+            grid[row * width + col]
+        }))
+    }
+}
+
+impl Pattern {
+    pub fn from_cells(
+        height: usize,
+        width: usize,
+        terrain: impl Fn(usize, usize) -> Terrain,
+    ) -> Pattern {
+        let mut rows = Lines::new(height, width);
+        let mut columns = Lines::new(width, height);
+        for row in 0..height {
+            for col in 0..width {
+                if terrain(row, col) == Terrain::Rock {
+                    rows.set(row, col);
+                    columns.set(col, row);
+                }
+            }
+        }
+        Pattern {
+            height,
+            width,
+            rows,
+            columns,
+        }
+    }
+
+    /// A random pattern with a horizontal mirror after `mirror_row` rows.
+    pub fn generate(height: usize, width: usize, mirror_row: usize, seed: u64) -> Pattern {
+        let mut state = seed;
+        let cells: Vec<Vec<Terrain>> = (0..height)
+            .map(|_| {
+                (0..width)
+                    .map(|_| {
+                        state = state
+                            .wrapping_mul(6364136223846793005)
+                            .wrapping_add(1442695040888963407);
+                        match state >> 63 {
+                            0 => Terrain::Ash,
+                            _ => Terrain::Rock,
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        Pattern::from_cells(height, width, |row, col| {
+            match (2 * mirror_row).checked_sub(row + 1) {
+                Some(reflected) if row >= mirror_row => cells[reflected][col],
+                _ => cells[row][col],
+            }
+        })
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Axis {
+    /// Mirror between two rows.
+    Horizontal,
+    /// Mirror between two columns.
+    Vertical,
+}
+
+/// A mirror line and the cells that must change for the reflection to be exact.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Mirror {
+    pub axis: Axis,
+    /// Number of rows above (or columns left of) the line.
+    pub position: usize,
+    /// (row, col) of each smudge, taken on the top or left side of the line.
+    pub smudges: Vec<(usize, usize)>,
+}
+
+impl Mirror {
+    pub fn score(&self) -> usize {
+        match self.axis {
+            Axis::Horizontal => self.position * 100,
+            Axis::Vertical => self.position,
+        }
+    }
+}
+
+impl Pattern {
+    pub fn get(&self, row: usize, col: usize) -> Terrain {
+        match self.rows.get(row, col) {
+            true => Terrain::Rock,
+            false => Terrain::Ash,
+        }
+    }
+
+    /// Mismatching cells for a mirror after `position` rows or columns, or `None` once there
+    /// are more than `max_smudges`. Each pair of lines is compared word by word, and only
+    /// located bit by bit when it differs.
+    fn smudges(
+        &self,
+        axis: Axis,
+        position: usize,
+        max_smudges: usize,
+    ) -> Option<Vec<(usize, usize)>> {
+        let (lines, num_lines) = match axis {
+            Axis::Horizontal => (&self.rows, self.height),
+            Axis::Vertical => (&self.columns, self.width),
+        };
+        let pairs = || (0..position).rev().zip(position..num_lines);
+        let mut num_differences = 0;
+        for (l1, l2) in pairs() {
+            num_differences += lines.differences(l1, l2);
+            if num_differences > max_smudges {
+                return None;
+            }
+        }
+        let smudges = pairs()
+            .flat_map(|(l1, l2)| {
+                lines.difference_positions(l1, l2).map(move |i| match axis {
+                    Axis::Horizontal => (l1, i),
+                    Axis::Vertical => (i, l1),
+                })
+            })
+            .collect();
+        Some(smudges)
+    }
+
+    /// Every mirror line with exactly `num_smudges` mismatching cells.
+    pub fn find_mirrors(&self, num_smudges: usize) -> Vec<Mirror> {
+        [Axis::Vertical, Axis::Horizontal]
+            .into_iter()
+            .flat_map(|axis| {
+                let lines = match axis {
+                    Axis::Horizontal => self.height,
+                    Axis::Vertical => self.width,
+                };
+                (1..lines).filter_map(move |position| {
+                    self.smudges(axis, position, num_smudges)
+                        .filter(|smudges| smudges.len() == num_smudges)
+                        .map(|smudges| Mirror {
+                            axis,
+                            position,
+                            smudges,
+                        })
+                })
+            })
+            .collect()
+    }
+
+    pub fn get_score(&self, num_smudges: usize) -> usize {
+        self.find_mirrors(num_smudges)
+            .iter()
+            .map(Mirror::score)
+            .sum()
+    }
+
+    /// The pattern with `>`/`<` (or `v`/`^`) marking the mirror line and `*` on each smudge.
+    pub fn render_mirror(&self, mirror: &Mirror) -> String {
+        let mut rendered = String::new();
+        let marker = |i: usize, before: char, after: char| {
+            if i + 1 == mirror.position {
+                before
+            } else if i == mirror.position {
+                after
+            } else {
+                ' '
+            }
+        };
+        if mirror.axis == Axis::Vertical {
+            rendered.push(' ');
+            rendered.extend((0..self.width).map(|col| marker(col, '>', '<')));
+            rendered.push('\n');
+        }
+        for row in 0..self.height {
+            rendered.push(match mirror.axis {
+                Axis::Horizontal => marker(row, 'v', '^'),
+                Axis::Vertical => ' ',
+            });
+            rendered.extend((0..self.width).map(|col| {
+                if mirror.smudges.contains(&(row, col)) {
+                    '*'
+                } else if self.get(row, col) == Terrain::Rock {
+                    '#'
+                } else {
+                    '.'
+                }
+            }));
+            rendered.push('\n');
+        }
+        rendered
+    }
+
+    /// Every symmetry of the pattern other than the edge-to-edge mirrors of `find_mirrors`.
+    /// Sub-rectangles are only reported when they reflect at least `min_reach` lines on each
+    /// side of the mirror, over at least `min_span` lines along it.
+    pub fn symmetries(&self, min_reach: usize, min_span: usize) -> Vec<Symmetry> {
+        let mut symmetries = Vec::new();
+        let (h, w) = (self.height, self.width);
+        let all_cells = |reflect: &dyn Fn(usize, usize) -> (usize, usize)| {
+            (0..h).all(|row| {
+                (0..w).all(|col| {
+                    let (r, c) = reflect(row, col);
+                    self.get(row, col) == self.get(r, c)
+                })
+            })
+        };
+        if all_cells(&|row, col| (h - 1 - row, w - 1 - col)) {
+            symmetries.push(Symmetry::Rotation);
+        }
+        if h == w {
+            // Transposing swaps the packed rows and columns, so they must be equal.
+            if self.rows == self.columns {
+                symmetries.push(Symmetry::Diagonal);
+            }
+            if all_cells(&|row, col| (w - 1 - col, h - 1 - row)) {
+                symmetries.push(Symmetry::AntiDiagonal);
+            }
+        }
+        for (axis, lines, num_lines) in [
+            (Axis::Horizontal, &self.rows, h),
+            (Axis::Vertical, &self.columns, w),
+        ] {
+            let length = match axis {
+                Axis::Horizontal => w,
+                Axis::Vertical => h,
+            };
+            for position in 1..num_lines {
+                let edge = position.min(num_lines - position);
+                // How many line pairs reflect at each cell along the mirror.
+                let mut reach = vec![None; length];
+                for k in 0..edge {
+                    for i in lines.difference_positions(position - 1 - k, position + k) {
+                        reach[i].get_or_insert(k);
+                    }
+                }
+                let reach: Vec<usize> = reach.into_iter().map(|r| r.unwrap_or(edge)).collect();
+                // Each maximal rectangle is as tall as its shortest cell: widen every cell's
+                // reach as far as its neighbours allow.
+                let mut rectangles: Vec<(usize, Range<usize>)> = (0..length)
+                    .filter(|&i| reach[i] >= min_reach.max(1))
+                    .map(|i| {
+                        let start = (0..i).rev().take_while(|&j| reach[j] >= reach[i]).count();
+                        let end = (i + 1..length)
+                            .take_while(|&j| reach[j] >= reach[i])
+                            .count();
+                        (reach[i], i - start..i + 1 + end)
+                    })
+                    .filter(|(r, span)| {
+                        span.len() >= min_span && (*r < edge || span.len() < length)
+                    })
+                    .collect();
+                rectangles.sort_by_key(|(r, span)| (span.start, span.end, *r));
+                rectangles.dedup();
+                symmetries.extend(rectangles.into_iter().map(|(reach, span)| {
+                    Symmetry::SubRectangle {
+                        axis,
+                        position,
+                        reach,
+                        span,
+                    }
+                }));
+            }
+        }
+        symmetries
+    }
+}
+
+/// Symmetries beyond the mirror lines scored by the puzzle.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Symmetry {
+    /// Unchanged by a half turn.
+    Rotation,
+    /// Unchanged by swapping rows and columns.
+    Diagonal,
+    /// Unchanged by reflecting across the other diagonal.
+    AntiDiagonal,
+    /// A mirror limited to part of the pattern: `reach` lines on each side of `position`
+    /// reflect, but only over the columns (or rows, for a vertical mirror) in `span`.
+    SubRectangle {
+        axis: Axis,
+        position: usize,
+        reach: usize,
+        span: Range<usize>,
+    },
+}
+
+pub fn parse_patterns(input: &str) -> Vec<Pattern> {
+    input
+        .split("\n\n")
+        .map(|paragraph| Pattern::from_str(paragraph).unwrap())
+        .collect()
+}
+
+pub const USAGE: &str = "Usage: [--smudges K] [--show] [--symmetries MIN_REACH] [--min-span N]";
+
+/// Command line of both parts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Args {
+    pub num_smudges: usize,
+    /// Draw every mirror over its pattern.
+    pub show: bool,
+    /// List the symmetries reaching at least this far.
+    pub min_reach: Option<usize>,
+    pub min_span: usize,
+}
+
+/// Parses the arguments shared by both parts, which only differ in the default `num_smudges`.
+pub fn parse_args(
+    args: impl IntoIterator<Item = String>,
+    num_smudges: usize,
+) -> Result<Args, String> {
+    let mut parsed = Args {
+        num_smudges,
+        show: false,
+        min_reach: None,
+        min_span: 2,
+    };
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut number = || {
+            let value = args.next().unwrap_or_default();
+            value
+                .parse()
+                .map_err(|_| format!("{arg} expects a number, got {value:?}"))
+        };
+        match arg.as_str() {
+            "--smudges" => parsed.num_smudges = number()?,
+            "--show" => parsed.show = true,
+            "--symmetries" => parsed.min_reach = Some(number()?),
+            "--min-span" => parsed.min_span = number()?,
+            _ => return Err(format!("unknown argument {arg}")),
+        }
+    }
+    Ok(parsed)
+}
+
+pub fn sum_scores(patterns: &[Pattern], num_smudges: usize) -> usize {
+    patterns
+        .iter()
+        .map(|pattern| pattern.get_score(num_smudges))
+        .sum()
+}
+
+/// Every mirror with `num_smudges` smudges, drawn over its pattern.
+pub fn mirrors_report(patterns: &[Pattern], num_smudges: usize) -> String {
+    let mut report = String::new();
+    for (i, pattern) in patterns.iter().enumerate() {
+        for mirror in pattern.find_mirrors(num_smudges) {
+            report += &format!(
+                "pattern {i}: {:?} mirror after {}, smudges {:?}\n",
+                mirror.axis, mirror.position, mirror.smudges
+            );
+            report += &pattern.render_mirror(&mirror);
+            report += "\n";
+        }
+    }
+    report
+}
+
+/// One line of symmetries per pattern.
+pub fn symmetries_report(patterns: &[Pattern], min_reach: usize, min_span: usize) -> String {
+    patterns
+        .iter()
+        .enumerate()
+        .map(|(i, pattern)| {
+            format!(
+                "pattern {i}: {:?}\n",
+                pattern.symmetries(min_reach, min_span)
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cell by cell count of the differences across a mirror line.
+    fn naive_differences(pattern: &Pattern, axis: Axis, position: usize) -> usize {
+        let mut differences = 0;
+        for row in 0..pattern.height {
+            for col in 0..pattern.width {
+                let reflected = match axis {
+                    Axis::Horizontal if row < position => (2 * position - row - 1, col),
+                    Axis::Vertical if col < position => (row, 2 * position - col - 1),
+                    _ => continue,
+                };
+                if reflected.0 < pattern.height
+                    && reflected.1 < pattern.width
+                    && pattern.get(row, col) != pattern.get(reflected.0, reflected.1)
+                {
+                    differences += 1;
+                }
+            }
+        }
+        differences
+    }
+
+    #[test]
+    fn command_line() {
+        let args = |args: &[&str]| parse_args(args.iter().map(|arg| arg.to_string()), 1);
+        assert_eq!(
+            args(&["--show", "--symmetries", "3", "--min-span", "4"]),
+            Ok(Args {
+                num_smudges: 1,
+                show: true,
+                min_reach: Some(3),
+                min_span: 4
+            })
+        );
+        assert_eq!(
+            args(&["--smudges", "0"]).map(|parsed| parsed.num_smudges),
+            Ok(0)
+        );
+        assert_eq!(
+            args(&["--shwo"]),
+            Err("unknown argument --shwo".to_string())
+        );
+        assert!(args(&["--smudges"]).is_err());
+    }
+
+    #[test]
+    fn example_scores() {
+        let patterns = parse_patterns(include_str!("../input_test.txt"));
+        let scores = |k| patterns.iter().map(|p| p.get_score(k)).collect::<Vec<_>>();
+        assert_eq!(scores(0), vec![5, 400]);
+        assert_eq!(scores(1), vec![300, 100]);
+        assert_eq!(sum_scores(&patterns, 0), 405);
+        assert_eq!(sum_scores(&patterns, 1), 400);
+    }
+
+    #[test]
+    fn smudge_locations() {
+        let patterns = parse_patterns(include_str!("../input_test.txt"));
+        assert_eq!(
+            patterns[0].find_mirrors(1),
+            vec![Mirror {
+                axis: Axis::Horizontal,
+                position: 3,
+                smudges: vec![(0, 0)],
+            }]
+        );
+        assert_eq!(
+            patterns[1].find_mirrors(1),
+            vec![Mirror {
+                axis: Axis::Horizontal,
+                position: 1,
+                smudges: vec![(0, 4)],
+            }]
+        );
+        // Every line is a mirror once enough smudges are allowed.
+        let pattern = &patterns[1];
+        let lines = pattern.width - 1 + pattern.height - 1;
+        let found: usize = (0..=pattern.width * pattern.height)
+            .map(|k| pattern.find_mirrors(k).len())
+            .sum();
+        assert_eq!(found, lines);
+    }
+
+    #[test]
+    fn render() {
+        let patterns = parse_patterns(include_str!("../input_test.txt"));
+        let mirror = &patterns[0].find_mirrors(0)[0];
+        let rendered = patterns[0].render_mirror(mirror);
+        assert_eq!(rendered.lines().next(), Some("     ><   "));
+        let report = mirrors_report(&patterns, 0);
+        assert_eq!(
+            report.lines().next(),
+            Some("pattern 0: Vertical mirror after 5, smudges []")
+        );
+        assert_eq!(report.lines().nth(1), Some("     ><   "));
+        let mirror = &patterns[0].find_mirrors(1)[0];
+        let rendered = patterns[0].render_mirror(mirror);
+        assert_eq!(rendered.lines().next(), Some(" *.##..##."));
+        assert_eq!(rendered.lines().nth(2), Some("v##......#"));
+    }
+
+    #[test]
+    fn packed_lines_match_cells() {
+        // Wider than a word, so lines span several `u64`.
+        let pattern = Pattern::generate(150, 130, 70, 3);
+        assert!(pattern.find_mirrors(0).contains(&Mirror {
+            axis: Axis::Horizontal,
+            position: 70,
+            smudges: vec![],
+        }));
+        for (axis, lines) in [(Axis::Horizontal, 150), (Axis::Vertical, 130)] {
+            for position in (1..lines).step_by(7) {
+                let differences = naive_differences(&pattern, axis, position);
+                let smudges = pattern.smudges(axis, position, usize::MAX).unwrap();
+                assert_eq!(smudges.len(), differences);
+                assert!(smudges.windows(2).all(|pair| pair[0] != pair[1]));
+            }
+        }
+    }
+
+    #[test]
+    fn symmetries() {
+        let pattern = |s: &str| Pattern::from_str(s).unwrap();
+        // A half turn and both diagonals. Spans of 3 keep small sub-rectangles out.
+        assert_eq!(
+            pattern("#..\n.#.\n..#").symmetries(1, 3),
+            vec![
+                Symmetry::Rotation,
+                Symmetry::Diagonal,
+                Symmetry::AntiDiagonal
+            ]
+        );
+        assert_eq!(
+            pattern("###\n##.\n#..").symmetries(1, 3),
+            vec![Symmetry::Diagonal]
+        );
+        assert_eq!(
+            pattern("#..\n##.\n###").symmetries(1, 3),
+            vec![Symmetry::AntiDiagonal]
+        );
+        assert_eq!(
+            pattern("##.\n.##").symmetries(1, 3),
+            vec![Symmetry::Rotation]
+        );
+        // Rows 1 and 2 match, rows 0 and 3 only in columns 1 and 3.
+        let band = pattern("#...\n.#.#\n.#.#\n..#.");
+        let horizontal = |position, reach, span| Symmetry::SubRectangle {
+            axis: Axis::Horizontal,
+            position,
+            reach,
+            span,
+        };
+        assert_eq!(band.symmetries(1, 2), vec![horizontal(2, 1, 0..4)]);
+        assert_eq!(band.symmetries(2, 2), vec![]);
+        assert_eq!(
+            band.symmetries(2, 1),
+            vec![horizontal(2, 2, 1..2), horizontal(2, 2, 3..4)]
+        );
+        // Columns 0 to 2 mirror around the middle, columns 3 and 4 only partly.
+        let partial = pattern("##.#.\n#..##\n#..#.\n##..#").symmetries(1, 3);
+        assert!(partial.contains(&horizontal(2, 2, 0..3)));
+        assert!(partial.contains(&horizontal(2, 1, 0..4)));
+        // The example mirrors reach an edge across the whole pattern, so they are left out.
+        let patterns = parse_patterns(include_str!("../input_test.txt"));
+        assert!(!patterns[0]
+            .symmetries(1, 1)
+            .contains(&Symmetry::SubRectangle {
+                axis: Axis::Vertical,
+                position: 5,
+                reach: 4,
+                span: 0..7,
+            }));
+    }
+}
@@ -0,0 +1,451 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::str::FromStr;
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum AlmanacError {
+    Parse(String),
+    UnknownCategory(String),
+    NoPath {
+        from: String,
+        to: String,
+    },
+    Overlap {
+        map: String,
+        first: usize,
+        second: usize,
+    },
+    BrokenChain {
+        expected: String,
+        found: String,
+    },
+    RepeatedCategory(String),
+    NotInvertible {
+        map: String,
+        value: u64,
+    },
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Adder {
+    pub start_dest: u64,
+    pub start_src: u64,
+    pub range: u64,
+}
+
+impl Adder {
+    pub fn apply(&self, value: u64) -> Option<u64> {
+        if self.start_src <= value && value < self.start_src + self.range {
+            let diff = value - self.start_src;
+            Some(self.start_dest + diff)
+        } else {
+            None
+        }
+    }
+    pub fn inverted(&self) -> Adder {
+        Adder {
+            start_dest: self.start_src,
+            start_src: self.start_dest,
+            range: self.range,
+        }
+    }
+}
+
+impl FromStr for Adder {
+    type Err = AlmanacError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let vec_numbers = s
+            .split_whitespace()
+            .map(|num_str| num_str.parse::<u64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| AlmanacError::Parse(s.to_string()))?;
+        match vec_numbers[..] {
+            [start_dest, start_src, range] => Ok(Adder {
+                start_dest,
+                start_src,
+                range,
+            }),
+            _ => Err(AlmanacError::Parse(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Converter {
+    pub source: String,
+    pub destination: String,
+    pub vector_map: Vec<Adder>,
+}
+
+impl FromStr for Converter {
+    type Err = AlmanacError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut iter_lines = s.lines();
+        let header = iter_lines.next().unwrap_or_default();
+        let (source, destination) = header
+            .strip_suffix(" map:")
+            .and_then(|name| name.split_once("-to-"))
+            .ok_or_else(|| AlmanacError::Parse(header.to_string()))?;
+        let vector_map = iter_lines
+            .filter(|line| !line.trim().is_empty())
+            .map(Adder::from_str)
+            .collect::<Result<_, _>>()?;
+        Ok(Converter {
+            source: source.to_string(),
+            destination: destination.to_string(),
+            vector_map,
+        })
+    }
+}
+
+impl Converter {
+    pub fn name(&self) -> String {
+        format!("{}-to-{}", self.source, self.destination)
+    }
+    /// Converts with the first adder whose source range contains `value`.
+    pub fn convert(&self, value: u64) -> u64 {
+        self.vector_map
+            .iter()
+            .find_map(|adder| adder.apply(value))
+            .unwrap_or(value)
+    }
+    /// Finds the value that `convert` maps onto `value`, using the inverted adders.
+    /// Values outside every source range map onto themselves, so they are also candidates.
+    /// With overlapping ranges an adder can be shadowed by an earlier one, so each candidate
+    /// is checked against the first-match `convert`.
+    pub fn convert_back(&self, value: u64) -> Result<u64, AlmanacError> {
+        let mut preimages: Vec<u64> = self
+            .vector_map
+            .iter()
+            .filter_map(|adder| adder.inverted().apply(value))
+            .chain(std::iter::once(value))
+            .filter(|&candidate| self.convert(candidate) == value)
+            .collect();
+        preimages.sort_unstable();
+        preimages.dedup();
+        match preimages[..] {
+            [preimage] => Ok(preimage),
+            _ => Err(AlmanacError::NotInvertible {
+                map: self.name(),
+                value,
+            }),
+        }
+    }
+    /// Reports overlapping source ranges and the holes left between them.
+    pub fn lint(&self) -> Vec<Lint> {
+        let mut indices: Vec<usize> = (0..self.vector_map.len()).collect();
+        indices.sort_by_key(|&i| self.vector_map[i].start_src);
+        let mut lints = Vec::new();
+        for (position, &i_first) in indices.iter().enumerate() {
+            let first = &self.vector_map[i_first];
+            let first_end = first.start_src + first.range;
+            for &i_second in indices[position + 1..].iter() {
+                let second = &self.vector_map[i_second];
+                if second.start_src >= first_end {
+                    break;
+                }
+                lints.push(Lint::Overlap {
+                    first: i_first,
+                    second: i_second,
+                    start: second.start_src,
+                    end: first_end.min(second.start_src + second.range),
+                });
+            }
+        }
+        let mut covered_end = None;
+        for &i in indices.iter() {
+            let adder = &self.vector_map[i];
+            if let Some(end) = covered_end {
+                if end < adder.start_src {
+                    lints.push(Lint::Gap {
+                        start: end,
+                        end: adder.start_src,
+                    });
+                }
+            }
+            let adder_end = adder.start_src + adder.range;
+            covered_end = Some(covered_end.map_or(adder_end, |end: u64| end.max(adder_end)));
+        }
+        lints
+    }
+    fn check_overlap(&self) -> Result<(), AlmanacError> {
+        match self
+            .lint()
+            .into_iter()
+            .find(|lint| matches!(lint, Lint::Overlap { .. }))
+        {
+            Some(Lint::Overlap { first, second, .. }) => Err(AlmanacError::Overlap {
+                map: self.name(),
+                first,
+                second,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Problem found in the source ranges of a map, as half-open `start..end` ranges.
+#[derive(PartialEq, Eq, Debug)]
+pub enum Lint {
+    Overlap {
+        first: usize,
+        second: usize,
+        start: u64,
+        end: u64,
+    },
+    Gap {
+        start: u64,
+        end: u64,
+    },
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Direction {
+    Forward,
+    Backward,
+}
+
+/// Sequence of maps linking two categories, each walked forward or backward.
+#[derive(Debug)]
+pub struct Chain<'a> {
+    pub steps: Vec<(&'a Converter, Direction)>,
+}
+
+impl Chain<'_> {
+    pub fn apply(&self, value: u64) -> Result<u64, AlmanacError> {
+        self.steps
+            .iter()
+            .try_fold(value, |value, (converter, direction)| match direction {
+                Direction::Forward => Ok(converter.convert(value)),
+                Direction::Backward => converter.convert_back(value),
+            })
+    }
+}
+
+#[derive(Debug)]
+pub struct Almanac {
+    pub seeds: Vec<u64>,
+    pub converters: Vec<Converter>,
+}
+
+impl FromStr for Almanac {
+    type Err = AlmanacError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut paragraph_iter = s.split_terminator("\n\n");
+        let seed_line = paragraph_iter.next().unwrap_or_default();
+        let seeds = seed_line
+            .strip_prefix("seeds:")
+            .ok_or_else(|| AlmanacError::Parse(seed_line.to_string()))?
+            .split_whitespace()
+            .map(|s| s.parse::<u64>())
+            .collect::<Result<_, _>>()
+            .map_err(|_| AlmanacError::Parse(seed_line.to_string()))?;
+        let converters = paragraph_iter
+            .map(Converter::from_str)
+            .collect::<Result<_, _>>()?;
+        Ok(Almanac { seeds, converters })
+    }
+}
+
+impl Almanac {
+    /// Checks that every map follows the previous one and that no source ranges overlap.
+    pub fn validate(&self) -> Result<(), AlmanacError> {
+        let mut seen_categories = HashSet::new();
+        for (i, converter) in self.converters.iter().enumerate() {
+            if i > 0 && self.converters[i - 1].destination != converter.source {
+                return Err(AlmanacError::BrokenChain {
+                    expected: self.converters[i - 1].destination.clone(),
+                    found: converter.source.clone(),
+                });
+            }
+            if i == 0 {
+                seen_categories.insert(converter.source.as_str());
+            }
+            if !seen_categories.insert(converter.destination.as_str()) {
+                return Err(AlmanacError::RepeatedCategory(
+                    converter.destination.clone(),
+                ));
+            }
+            converter.check_overlap()?;
+        }
+        Ok(())
+    }
+    /// Finds the maps to go through to convert a `from` value into a `to` value.
+    pub fn chain(&self, from: &str, to: &str) -> Result<Chain<'_>, AlmanacError> {
+        let mut graph: HashMap<&str, Vec<(&Converter, Direction, &str)>> = HashMap::new();
+        for converter in self.converters.iter() {
+            let (source, destination) = (converter.source.as_str(), converter.destination.as_str());
+            graph
+                .entry(source)
+                .or_default()
+                .push((converter, Direction::Forward, destination));
+            graph
+                .entry(destination)
+                .or_default()
+                .push((converter, Direction::Backward, source));
+        }
+        for category in [from, to] {
+            if !graph.contains_key(category) {
+                return Err(AlmanacError::UnknownCategory(category.to_string()));
+            }
+        }
+        let mut previous: HashMap<&str, (&Converter, Direction, &str)> = HashMap::new();
+        let mut queue = VecDeque::from([from]);
+        while let Some(category) = queue.pop_front() {
+            if category == to {
+                let mut steps = Vec::new();
+                let mut current = to;
+                while current != from {
+                    let (converter, direction, before) = previous[current];
+                    steps.push((converter, direction));
+                    current = before;
+                }
+                steps.reverse();
+                return Ok(Chain { steps });
+            }
+            for &(converter, direction, next) in graph[category].iter() {
+                if next != from && !previous.contains_key(next) {
+                    previous.insert(next, (converter, direction, category));
+                    queue.push_back(next);
+                }
+            }
+        }
+        Err(AlmanacError::NoPath {
+            from: from.to_string(),
+            to: to.to_string(),
+        })
+    }
+    pub fn lint(&self) -> Vec<(String, Vec<Lint>)> {
+        self.converters
+            .iter()
+            .map(|converter| (converter.name(), converter.lint()))
+            .collect()
+    }
+    pub fn lookup(&self, from: &str, to: &str, value: u64) -> Result<u64, AlmanacError> {
+        self.chain(from, to)?.apply(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_almanac() -> Almanac {
+        Almanac::from_str(include_str!("../input_test.txt")).unwrap()
+    }
+
+    #[test]
+    fn seed_to_location() {
+        let almanac = test_almanac();
+        assert_eq!(almanac.validate(), Ok(()));
+        let locations: Vec<u64> = almanac
+            .seeds
+            .iter()
+            .map(|&seed| almanac.lookup("seed", "location", seed).unwrap())
+            .collect();
+        assert_eq!(locations, vec![82, 43, 86, 35]);
+    }
+
+    #[test]
+    fn inverse_lookup() {
+        let almanac = test_almanac();
+        assert_eq!(almanac.lookup("location", "seed", 35), Ok(13));
+        assert_eq!(almanac.lookup("humidity", "soil", 78), Ok(81));
+        assert_eq!(almanac.lookup("soil", "soil", 12), Ok(12));
+    }
+
+    #[test]
+    fn unknown_category() {
+        let almanac = test_almanac();
+        assert_eq!(
+            almanac.lookup("seed", "gold", 1),
+            Err(AlmanacError::UnknownCategory("gold".to_string()))
+        );
+    }
+
+    #[test]
+    fn validation_errors() {
+        let overlapping = Almanac::from_str("seeds: 1\n\na-to-b map:\n0 10 5\n20 12 5").unwrap();
+        assert_eq!(
+            overlapping.validate(),
+            Err(AlmanacError::Overlap {
+                map: "a-to-b".to_string(),
+                first: 0,
+                second: 1
+            })
+        );
+        let broken =
+            Almanac::from_str("seeds: 1\n\na-to-b map:\n0 10 5\n\nc-to-d map:\n0 10 5").unwrap();
+        assert_eq!(
+            broken.validate(),
+            Err(AlmanacError::BrokenChain {
+                expected: "b".to_string(),
+                found: "c".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn first_match_wins() {
+        let converter = Converter::from_str("a-to-b map:\n100 10 5\n200 12 5").unwrap();
+        assert_eq!(converter.convert(11), 101);
+        assert_eq!(converter.convert(13), 103);
+        assert_eq!(converter.convert(15), 203);
+        assert_eq!(converter.convert(17), 17);
+    }
+
+    #[test]
+    fn invert_overlapping_ranges() {
+        let converter = Converter::from_str("a-to-b map:\n100 10 5\n200 12 5\n0 202 1").unwrap();
+        // 14 is shadowed by the first adder and 202 maps onto 0, so nothing reaches 202.
+        assert_eq!(converter.convert(14), 104);
+        assert_eq!(
+            converter.convert_back(202),
+            Err(AlmanacError::NotInvertible {
+                map: "a-to-b".to_string(),
+                value: 202
+            })
+        );
+        let converter = Converter::from_str("a-to-b map:\n100 10 5\n200 12 5").unwrap();
+        // 13 is shadowed by the first adder, leaving 201 as its own unique preimage.
+        assert_eq!(converter.convert_back(201), Ok(201));
+        // 15 is past the first range, so both 15 and 203 itself convert to 203.
+        assert_eq!(converter.convert(15), 203);
+        assert!(converter.convert_back(203).is_err());
+        for preimage in 0..300 {
+            let value = converter.convert(preimage);
+            if let Ok(found) = converter.convert_back(value) {
+                assert_eq!(converter.convert(found), value);
+            }
+        }
+    }
+
+    #[test]
+    fn lint_overlaps_and_gaps() {
+        let converter =
+            Converter::from_str("a-to-b map:\n100 10 5\n200 12 5\n300 20 2\n400 11 1").unwrap();
+        assert_eq!(
+            converter.lint(),
+            vec![
+                Lint::Overlap {
+                    first: 0,
+                    second: 3,
+                    start: 11,
+                    end: 12
+                },
+                Lint::Overlap {
+                    first: 0,
+                    second: 1,
+                    start: 12,
+                    end: 15
+                },
+                Lint::Gap { start: 17, end: 20 },
+            ]
+        );
+        let almanac = test_almanac();
+        let lints = almanac.lint();
+        assert_eq!(lints[0].0, "seed-to-soil");
+        assert!(lints
+            .iter()
+            .all(|(_, lints)| lints.iter().all(|lint| matches!(lint, Lint::Gap { .. }))));
+    }
+}
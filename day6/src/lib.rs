@@ -0,0 +1,302 @@
+use std::ops::RangeInclusive;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Race {
+    pub time: u128,
+    pub distance: u128,
+}
+
+impl Race {
+    /// Hold times `t` beating the record, i.e. solutions of `t * (time - t) > distance`.
+    pub fn winning_range(&self) -> Option<RangeInclusive<u128>> {
+        // The product peaks at half the race, so nothing wins if that does not.
+        let half = self.time / 2;
+        if !self.beats_record(half) {
+            return None;
+        }
+        // With time = 2 * half + parity and t = half - x, the product is
+        // half * (half + parity) - x * (x + parity), so x is about the square root of the
+        // peak minus the record. Past u128 the boundary is searched for instead.
+        let parity = self.time % 2;
+        let mut first_passing = match half
+            .checked_mul(half)
+            .and_then(|half_squared| half_squared.checked_add(parity * half))
+        {
+            Some(peak) => half - isqrt(peak - self.distance),
+            None => self.search_first_passing(half),
+        };
+        // Step to the exact boundary.
+        while first_passing > 0 && self.beats_record(first_passing - 1) {
+            first_passing -= 1;
+        }
+        while !self.beats_record(first_passing) {
+            first_passing += 1;
+        }
+        Some(first_passing..=self.time - first_passing)
+    }
+    /// Smallest winning hold time, by bisection below `half` where the product increases.
+    fn search_first_passing(&self, half: u128) -> u128 {
+        let (mut low, mut high) = (0, half);
+        while low < high {
+            let middle = low + (high - low) / 2;
+            if self.beats_record(middle) {
+                high = middle;
+            } else {
+                low = middle + 1;
+            }
+        }
+        low
+    }
+    pub fn num_ways(&self) -> u128 {
+        self.winning_range()
+            .map_or(0, |range| range.end() - range.start() + 1)
+    }
+    fn beats_record(&self, time_push: u128) -> bool {
+        // A product past u128 is beyond any record.
+        time_push
+            .checked_mul(self.time - time_push)
+            .is_none_or(|product| product > self.distance)
+    }
+}
+
+/// Largest `r` such that `r * r <= n`.
+pub fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    let mut root = 1u128 << (128 - n.leading_zeros()).div_ceil(2);
+    loop {
+        let next = (root + n / root) / 2;
+        if next >= root {
+            return root;
+        }
+        root = next;
+    }
+}
+
+fn parse_line<'a>(line: &'a str, name: &str) -> impl Iterator<Item = &'a str> {
+    line.strip_prefix(name)
+        .and_then(|rest| rest.strip_prefix(':'))
+        .unwrap_or_else(|| panic!("expected a {} line", name))
+        .split_whitespace()
+}
+
+/// Each column of the input is its own race.
+pub fn parse_races(input: &str) -> Vec<Race> {
+    let mut iter_lines = input.lines();
+    let times = parse_line(iter_lines.next().unwrap(), "Time");
+    let distances = parse_line(iter_lines.next().unwrap(), "Distance");
+    times
+        .zip(distances)
+        .map(|(time_str, distance_str)| Race {
+            time: time_str.parse().unwrap(),
+            distance: distance_str.parse().unwrap(),
+        })
+        .collect()
+}
+
+/// The spaces between numbers are bad kerning: each line is a single number.
+pub fn parse_kerned_race(input: &str) -> Race {
+    let mut iter_lines = input.lines();
+    let time: String = parse_line(iter_lines.next().unwrap(), "Time").collect();
+    let distance: String = parse_line(iter_lines.next().unwrap(), "Distance").collect();
+    Race {
+        time: time.parse().unwrap(),
+        distance: distance.parse().unwrap(),
+    }
+}
+
+/// Which reading of the sheet to report on.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Mode {
+    /// One race per column.
+    Races,
+    /// A single race, read with `parse_kerned_race`.
+    Kerning,
+}
+
+pub const USAGE: &str = "Usage: [--races] [--kerning] [PATH]";
+
+/// Command line of both parts.
+#[derive(PartialEq, Eq, Debug, Default)]
+pub struct Options {
+    /// Reports to print, in order. Empty when no flag is given.
+    pub modes: Vec<Mode>,
+    /// Sheet to read instead of the puzzle input.
+    pub path: Option<String>,
+}
+
+impl Options {
+    pub fn input(&self, puzzle_input: &str) -> std::io::Result<String> {
+        match &self.path {
+            Some(path) => std::fs::read_to_string(path),
+            None => Ok(puzzle_input.to_string()),
+        }
+    }
+}
+
+/// Each flag adds its report, and any other argument is the path of the sheet.
+/// Returns the argument back if it looks like a flag but is not one.
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options::default();
+    for arg in args {
+        let mode = match arg.as_str() {
+            "--races" => Mode::Races,
+            "--kerning" => Mode::Kerning,
+            _ if arg.starts_with("--") => return Err(arg),
+            _ => {
+                options.path = Some(arg);
+                continue;
+            }
+        };
+        if !options.modes.contains(&mode) {
+            options.modes.push(mode);
+        }
+    }
+    Ok(options)
+}
+
+/// Winning hold times of each race and their product, or the ways to win the kerned race.
+pub fn report(input: &str, mode: Mode) -> String {
+    let mut report = String::new();
+    match mode {
+        Mode::Races => {
+            let vec_races = parse_races(input);
+            for race in vec_races.iter() {
+                report += &match race.winning_range() {
+                    Some(range) => format!(
+                        "time {} distance {}: hold {}..={} ({} ways)\n",
+                        race.time,
+                        race.distance,
+                        range.start(),
+                        range.end(),
+                        race.num_ways()
+                    ),
+                    None => format!(
+                        "time {} distance {}: no way to win\n",
+                        race.time, race.distance
+                    ),
+                };
+            }
+            let product: u128 = vec_races.iter().map(|race| race.num_ways()).product();
+            report += &format!("races: {}\n", product);
+        }
+        Mode::Kerning => {
+            report += &format!("kerning: {}\n", parse_kerned_race(input).num_ways());
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brute_force(race: &Race) -> u128 {
+        (0..=race.time)
+            .filter(|&time_push| race.beats_record(time_push))
+            .count() as u128
+    }
+
+    #[test]
+    fn isqrt_boundaries() {
+        for n in 0..10_000u128 {
+            let root = isqrt(n);
+            assert!(root * root <= n && (root + 1) * (root + 1) > n);
+        }
+        assert_eq!(isqrt(u128::MAX), u64::MAX as u128);
+        let big = (1u128 << 60) + 12345;
+        assert_eq!(isqrt(big * big), big);
+        assert_eq!(isqrt(big * big - 1), big - 1);
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        for time in 0..60 {
+            for distance in 0..(time * time / 4 + 3) {
+                let race = Race { time, distance };
+                assert_eq!(race.num_ways(), brute_force(&race), "{:?}", race);
+            }
+        }
+    }
+
+    #[test]
+    fn exact_root_is_not_a_win() {
+        // 10 * (30 - 10) == 200 ties the record
+        let race = Race {
+            time: 30,
+            distance: 200,
+        };
+        assert_eq!(race.winning_range(), Some(11..=19));
+    }
+
+    #[test]
+    fn huge_race() {
+        let time = u64::MAX as u128;
+        let race = Race { time, distance: 0 };
+        assert_eq!(race.winning_range(), Some(1..=time - 1));
+        let half = time / 2;
+        let race = Race {
+            time,
+            distance: half * (time - half),
+        };
+        assert_eq!(race.num_ways(), 0);
+        let race = Race {
+            time,
+            distance: half * (time - half) - 1,
+        };
+        assert_eq!(race.winning_range(), Some(half..=time - half));
+    }
+
+    #[test]
+    fn race_beyond_u64() {
+        let time = 1 << 70;
+        let race = Race { time, distance: 5 };
+        assert_eq!(race.winning_range(), Some(1..=time - 1));
+        let time = 1 << 100;
+        let first = 1 << 20;
+        let race = Race {
+            time,
+            distance: first * (time - first) - 1,
+        };
+        assert_eq!(race.winning_range(), Some(first..=time - first));
+        let race = Race {
+            time: u128::MAX,
+            distance: u128::MAX,
+        };
+        assert_eq!(race.winning_range(), Some(2..=u128::MAX - 2));
+        // Odd times around the largest exactly squarable half.
+        for time in [(1 << 65) - 1, (1 << 65) + 1, (1 << 66) + 1] {
+            let first = 12345;
+            let race = Race {
+                time,
+                distance: first * (time - first) - 1,
+            };
+            assert_eq!(race.winning_range(), Some(first..=time - first));
+        }
+    }
+
+    #[test]
+    fn both_modes() {
+        let args = |args: &[&str]| parse_args(args.iter().map(|arg| arg.to_string()));
+        assert_eq!(
+            args(&["--races", "--kerning", "sheet.txt"]),
+            Ok(Options {
+                modes: vec![Mode::Races, Mode::Kerning],
+                path: Some("sheet.txt".to_string())
+            })
+        );
+        assert_eq!(args(&[]), Ok(Options::default()));
+        assert_eq!(args(&["--race"]), Err("--race".to_string()));
+        let input = "Time:      7  15   30\nDistance:  9  40  200";
+        assert_eq!(
+            report(input, Mode::Races),
+            "time 7 distance 9: hold 2..=5 (4 ways)
+time 15 distance 40: hold 4..=11 (8 ways)
+time 30 distance 200: hold 11..=19 (9 ways)
+races: 288
+"
+        );
+        assert_eq!(report(input, Mode::Kerning), "kerning: 71503\n");
+    }
+}
@@ -1,45 +1,23 @@
+use day6::{parse_args, parse_races, report, USAGE};
+
+/// Prints the answer, or with `--races` and `--kerning` the reports of `day6::report`.
 fn main() {
-    let input = include_str!("../input.txt");
-    dbg!(part_1(input));
+    let options = parse_args(std::env::args().skip(1))
+        .unwrap_or_else(|arg| panic!("unknown flag {arg}\n{USAGE}"));
+    let input = options.input(include_str!("../input.txt")).unwrap();
+    if options.modes.is_empty() {
+        dbg!(part_1(&input));
+    }
+    for &mode in options.modes.iter() {
+        print!("{}", report(&input, mode));
+    }
 }
 
 fn part_1(input: &str) -> String {
-    let mut iter_lines = input.split_terminator("\n");
-    let time_line = iter_lines.next().unwrap();
-    let distance_line = iter_lines.next().unwrap();
-    let times = time_line
-        .split_whitespace()
-        .skip(1)
-        .map(|time_str| time_str.parse::<u32>().unwrap());
-    let distances = distance_line
-        .split_whitespace()
-        .skip(1)
-        .map(|distance_str| distance_str.parse::<u32>().unwrap());
-    let result = times
-        .zip(distances)
-        .map(|(time, distance)| {
-            let first_passing = (0..)
-                .find_map(|time_push| {
-                    if time_push * (time - time_push) > distance {
-                        Some(time_push)
-                    } else {
-                        None
-                    }
-                })
-                .unwrap();
-            let last_passing = (0..time)
-                .rev()
-                .find_map(|time_push| {
-                    if time_push * (time - time_push) > distance {
-                        Some(time_push)
-                    } else {
-                        None
-                    }
-                })
-                .unwrap();
-            last_passing - first_passing + 1
-        })
-        .fold(1, |acc, value| acc * value);
+    let result: u128 = parse_races(input)
+        .iter()
+        .map(|race| race.num_ways())
+        .product();
     result.to_string()
 }
 
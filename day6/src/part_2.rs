@@ -1,52 +1,20 @@
+use day6::{parse_args, parse_kerned_race, report, USAGE};
+
+/// Prints the answer, or with `--races` and `--kerning` the reports of `day6::report`.
 fn main() {
-    let input = include_str!("../input.txt");
-    dbg!(part_1(input));
+    let options = parse_args(std::env::args().skip(1))
+        .unwrap_or_else(|arg| panic!("unknown flag {arg}\n{USAGE}"));
+    let input = options.input(include_str!("../input.txt")).unwrap();
+    if options.modes.is_empty() {
+        dbg!(part_1(&input));
+    }
+    for &mode in options.modes.iter() {
+        print!("{}", report(&input, mode));
+    }
 }
 
 fn part_1(input: &str) -> String {
-    let mut iter_lines = input.split_terminator("\n");
-    let time_line = iter_lines.next().unwrap();
-    let distance_line = iter_lines.next().unwrap();
-    let time: u64 = time_line
-        .split(":")
-        .skip(1)
-        .map(|s| {
-            let mut vec_chars: Vec<_> = s.to_string().chars().collect();
-            vec_chars.retain(|&c| c != ' ');
-            vec_chars.into_iter().collect::<String>().parse().unwrap()
-        })
-        .next()
-        .unwrap();
-    let distance: u64 = distance_line
-        .split(":")
-        .skip(1)
-        .map(|s| {
-            let mut vec_chars: Vec<_> = s.to_string().chars().collect();
-            vec_chars.retain(|&c| c != ' ');
-            vec_chars.into_iter().collect::<String>().parse().unwrap()
-        })
-        .next()
-        .unwrap();
-    let first_passing = (0..)
-        .find_map(|time_push| {
-            if time_push * (time - time_push) > distance {
-                Some(time_push)
-            } else {
-                None
-            }
-        })
-        .unwrap();
-    let last_passing = (0..time)
-        .rev()
-        .find_map(|time_push| {
-            if time_push * (time - time_push) > distance {
-                Some(time_push)
-            } else {
-                None
-            }
-        })
-        .unwrap();
-    let result = last_passing - first_passing + 1;
+    let result = parse_kerned_race(input).num_ways();
     result.to_string()
 }
 
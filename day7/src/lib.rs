@@ -0,0 +1,527 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum ParseError {
+    UnknownCard(char),
+    WrongLength(usize),
+    Bid(String),
+}
+
+/// How a game of Camel Cards is played: card strengths, wildcards, hand size and hand types.
+#[derive(Clone, Debug)]
+pub struct Rules {
+    /// Cards from weakest to strongest.
+    pub card_order: Vec<char>,
+    /// Cards that join the most common other card when classifying a hand.
+    pub wildcards: Vec<char>,
+    pub hand_size: usize,
+    /// Card counts, largest first, and the name of the hand they make.
+    pub hand_types: Vec<(Vec<usize>, Hand)>,
+}
+
+impl Rules {
+    pub fn new(card_order: &str, wildcards: &str) -> Rules {
+        let hand_types = vec![
+            (vec![5], FiveOfAKind),
+            (vec![4, 1], FourOfAKind),
+            (vec![3, 2], FullHouse),
+            (vec![3, 1, 1], ThreeOfAKind),
+            (vec![2, 2, 1], TwoPair),
+            (vec![2, 1, 1, 1], Pair),
+            (vec![1, 1, 1, 1, 1], HighCard),
+        ];
+        Rules {
+            card_order: card_order.chars().collect(),
+            wildcards: wildcards.chars().collect(),
+            hand_size: 5,
+            hand_types,
+        }
+    }
+    pub fn with_hand_size(self, hand_size: usize) -> Rules {
+        Rules { hand_size, ..self }
+    }
+    /// Names the hand made by `counts`, replacing any previous name for them.
+    /// The name does not change how the hand ranks.
+    pub fn with_hand_type(mut self, counts: Vec<usize>, hand: Hand) -> Rules {
+        self.hand_types.retain(|(other, _)| *other != counts);
+        self.hand_types.push((counts, hand));
+        self
+    }
+    pub fn standard() -> Rules {
+        Rules::new("23456789TJQKA", "")
+    }
+    /// `J` cards are jokers: wildcards, and the weakest individual card.
+    pub fn jokers() -> Rules {
+        Rules::new("J23456789TQKA", "J")
+    }
+    pub fn strength(&self, card: char) -> Result<usize, ParseError> {
+        self.card_order
+            .iter()
+            .position(|&c| c == card)
+            .ok_or(ParseError::UnknownCard(card))
+    }
+    /// Card the wildcards of a hand stand for: the most common other card, the strongest on ties.
+    pub fn substitution(&self, s: &str) -> Option<char> {
+        if !s.chars().any(|card| self.wildcards.contains(&card)) {
+            return None;
+        }
+        let mut hash_letters = HashMap::new();
+        for card in s.chars().filter(|card| !self.wildcards.contains(card)) {
+            *hash_letters.entry(card).or_insert(0) += 1;
+        }
+        hash_letters
+            .into_iter()
+            .max_by_key(|&(card, count)| (count, self.strength(card).ok()))
+            .map(|(card, _)| card)
+            .or_else(|| {
+                self.card_order
+                    .iter()
+                    .rev()
+                    .find(|card| !self.wildcards.contains(card))
+                    .copied()
+            })
+    }
+    pub fn classify(&self, s: &str) -> HandType {
+        let mut hash_letters = HashMap::new();
+        let mut num_wildcards = 0;
+        for card in s.chars() {
+            if self.wildcards.contains(&card) {
+                num_wildcards += 1;
+            } else {
+                *hash_letters.entry(card).or_insert(0) += 1;
+            }
+        }
+        let mut vec_values: Vec<usize> = hash_letters.into_values().collect();
+        vec_values.sort_by(|a, b| b.cmp(a));
+        match vec_values.first_mut() {
+            Some(most_common) => *most_common += num_wildcards,
+            None => vec_values.push(num_wildcards),
+        }
+        let name = self
+            .hand_types
+            .iter()
+            .find(|(counts, _)| *counts == vec_values)
+            .map(|(_, hand)| hand.clone());
+        HandType {
+            counts: vec_values,
+            name,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+pub struct Letter {
+    pub strength: usize,
+    pub card: char,
+}
+
+impl Letter {
+    pub fn parse(card: char, rules: &Rules) -> Result<Letter, ParseError> {
+        let strength = rules.strength(card)?;
+        Ok(Letter { strength, card })
+    }
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug)]
+pub struct Letters(pub Vec<Letter>);
+
+impl Letters {
+    pub fn parse(s: &str, rules: &Rules) -> Result<Letters, ParseError> {
+        let vec_letters = s
+            .chars()
+            .map(|card| Letter::parse(card, rules))
+            .collect::<Result<Vec<_>, _>>()?;
+        if vec_letters.len() != rules.hand_size {
+            return Err(ParseError::WrongLength(vec_letters.len()));
+        }
+        Ok(Letters(vec_letters))
+    }
+    pub fn cards(&self) -> String {
+        self.0.iter().map(|letter| letter.card).collect()
+    }
+}
+
+/// Card counts of a hand, largest first, wildcards included.
+/// Comparing the counts lexicographically ranks hands of any size.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug)]
+pub struct HandType {
+    pub counts: Vec<usize>,
+    /// Name of the counts in the `Rules::hand_types` table, if they have one.
+    pub name: Option<Hand>,
+}
+
+impl fmt::Display for HandType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.name {
+            Some(hand) => write!(f, "{}", hand),
+            None => {
+                let vec_counts: Vec<String> =
+                    self.counts.iter().map(|count| count.to_string()).collect();
+                write!(f, "{}", vec_counts.join("+"))
+            }
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug)]
+pub enum Hand {
+    HighCard,
+    Pair,
+    TwoPair,
+    ThreeOfAKind,
+    FullHouse,
+    FourOfAKind,
+    FiveOfAKind,
+    /// A house hand type.
+    Custom(String),
+}
+
+impl fmt::Display for Hand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Custom(name) => write!(f, "{}", name),
+            hand => write!(f, "{:?}", hand),
+        }
+    }
+}
+
+use Hand::*;
+
+/// Plays are ordered by hand type first, then card by card.
+#[derive(PartialEq, Eq, Ord, PartialOrd, Debug)]
+pub struct Play {
+    pub hand: HandType,
+    pub letters: Letters,
+    pub bid: u64,
+}
+
+impl Play {
+    pub fn parse(s: &str, rules: &Rules) -> Result<Play, ParseError> {
+        let (cards, bid_str) = s
+            .split_once(' ')
+            .ok_or_else(|| ParseError::Bid(s.to_string()))?;
+        let bid = bid_str
+            .trim()
+            .parse::<u64>()
+            .map_err(|_| ParseError::Bid(bid_str.to_string()))?;
+        Play::new(cards, bid, rules)
+    }
+    pub fn new(cards: &str, bid: u64, rules: &Rules) -> Result<Play, ParseError> {
+        let letters = Letters::parse(cards, rules)?;
+        let hand = rules.classify(cards);
+        Ok(Play { hand, letters, bid })
+    }
+}
+
+/// What decides the comparison between two plays.
+#[derive(PartialEq, Eq, Debug)]
+pub enum Reason {
+    HandType {
+        winner: HandType,
+        loser: HandType,
+    },
+    Card {
+        position: usize,
+        winner: Letter,
+        loser: Letter,
+    },
+    Tie,
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Reason::HandType { winner, loser } => write!(f, "{} beats {}", winner, loser),
+            Reason::Card {
+                position,
+                winner,
+                loser,
+            } => write!(
+                f,
+                "same hand type, card {} {} beats {}",
+                position + 1,
+                winner.card,
+                loser.card
+            ),
+            Reason::Tie => write!(f, "the hands are equal"),
+        }
+    }
+}
+
+pub fn compare(play_a: &Play, play_b: &Play) -> (Ordering, Reason) {
+    let ordering = play_a.hand.cmp(&play_b.hand);
+    if ordering != Ordering::Equal {
+        let (winner, loser) = match ordering {
+            Ordering::Greater => (play_a.hand.clone(), play_b.hand.clone()),
+            _ => (play_b.hand.clone(), play_a.hand.clone()),
+        };
+        return (ordering, Reason::HandType { winner, loser });
+    }
+    let letters_a = play_a.letters.0.iter().copied();
+    let letters_b = play_b.letters.0.iter().copied();
+    for (position, (letter_a, letter_b)) in letters_a.zip(letters_b).enumerate() {
+        let ordering = letter_a.cmp(&letter_b);
+        if ordering != Ordering::Equal {
+            let (winner, loser) = match ordering {
+                Ordering::Greater => (letter_a, letter_b),
+                _ => (letter_b, letter_a),
+            };
+            return (
+                ordering,
+                Reason::Card {
+                    position,
+                    winner,
+                    loser,
+                },
+            );
+        }
+    }
+    (Ordering::Equal, Reason::Tie)
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub struct Explanation {
+    pub cards: String,
+    pub hand: HandType,
+    pub substitution: Option<char>,
+    pub rank: usize,
+    pub bid: u64,
+    pub winnings: u64,
+}
+
+/// One line per play, from the weakest to the strongest.
+pub fn explain(mut vec_play: Vec<Play>, rules: &Rules) -> Vec<Explanation> {
+    vec_play.sort();
+    vec_play
+        .into_iter()
+        .enumerate()
+        .map(|(i, play)| {
+            let cards = play.letters.cards();
+            Explanation {
+                substitution: rules.substitution(&cards),
+                cards,
+                hand: play.hand,
+                rank: i + 1,
+                bid: play.bid,
+                winnings: (i + 1) as u64 * play.bid,
+            }
+        })
+        .collect()
+}
+
+fn substitution_str(explanation: &Explanation) -> String {
+    explanation
+        .substitution
+        .map_or("-".to_string(), |card| card.to_string())
+}
+
+pub fn explanation_table(explanations: &[Explanation]) -> String {
+    let width = explanations
+        .iter()
+        .map(|explanation| explanation.cards.len())
+        .max()
+        .unwrap_or(0)
+        .max(5);
+    let mut table = format!(
+        "{:<width$} {:<13} {:<5} {:>5} {:>5} {:>10}\n",
+        "cards", "hand", "wild", "rank", "bid", "winnings"
+    );
+    for explanation in explanations {
+        table.push_str(&format!(
+            "{:<width$} {:<13} {:<5} {:>5} {:>5} {:>10}\n",
+            explanation.cards,
+            explanation.hand.to_string(),
+            substitution_str(explanation),
+            explanation.rank,
+            explanation.bid,
+            explanation.winnings
+        ));
+    }
+    table
+}
+
+pub fn explanation_csv(explanations: &[Explanation]) -> String {
+    let mut csv = "cards,hand,wild,rank,bid,winnings\n".to_string();
+    for explanation in explanations {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            explanation.cards,
+            explanation.hand,
+            substitution_str(explanation),
+            explanation.rank,
+            explanation.bid,
+            explanation.winnings
+        ));
+    }
+    csv
+}
+
+/// Reasoning behind the total winnings.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Report {
+    /// One row per play, as an aligned table.
+    Explain,
+    /// The same rows as CSV.
+    Csv,
+    /// Why the first hand beats, loses to or ties with the second.
+    Why(String, String),
+}
+
+pub const USAGE: &str = "Usage: [--explain | --csv | --why HAND_A HAND_B]";
+
+/// Report asked for on the command line, if any. Both parts take the same arguments.
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Option<Report>, String> {
+    let args: Vec<String> = args.into_iter().collect();
+    match args.iter().map(|arg| arg.as_str()).collect::<Vec<_>>()[..] {
+        [] => Ok(None),
+        ["--explain"] => Ok(Some(Report::Explain)),
+        ["--csv"] => Ok(Some(Report::Csv)),
+        ["--why", cards_a, cards_b] => {
+            Ok(Some(Report::Why(cards_a.to_string(), cards_b.to_string())))
+        }
+        _ => Err(format!("unexpected arguments {}", args.join(" "))),
+    }
+}
+
+pub fn report(input: &str, rules: &Rules, report: &Report) -> Result<String, ParseError> {
+    Ok(match report {
+        Report::Explain => explanation_table(&explain(parse_plays(input, rules)?, rules)),
+        Report::Csv => explanation_csv(&explain(parse_plays(input, rules)?, rules)),
+        Report::Why(cards_a, cards_b) => {
+            let play_a = Play::new(cards_a, 0, rules)?;
+            let play_b = Play::new(cards_b, 0, rules)?;
+            let (ordering, reason) = compare(&play_a, &play_b);
+            let verdict = match ordering {
+                Ordering::Greater => "beats",
+                Ordering::Less => "loses to",
+                Ordering::Equal => "ties with",
+            };
+            format!("{} {} {}: {}\n", cards_a, verdict, cards_b, reason)
+        }
+    })
+}
+
+pub fn parse_plays(input: &str, rules: &Rules) -> Result<Vec<Play>, ParseError> {
+    input.lines().map(|line| Play::parse(line, rules)).collect()
+}
+
+/// Sum of each bid times the rank of its play, the weakest play having rank 1.
+pub fn total_winnings(mut vec_play: Vec<Play>) -> u64 {
+    vec_play.sort();
+    vec_play
+        .into_iter()
+        .enumerate()
+        .map(|(rank, play)| (rank + 1) as u64 * play.bid)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT_TEST: &str = include_str!("./input_test.txt");
+
+    #[test]
+    fn standard_rules() {
+        let vec_play = parse_plays(INPUT_TEST, &Rules::standard()).unwrap();
+        assert_eq!(total_winnings(vec_play), 6440);
+    }
+
+    #[test]
+    fn joker_rules() {
+        let vec_play = parse_plays(INPUT_TEST, &Rules::jokers()).unwrap();
+        assert_eq!(total_winnings(vec_play), 5905);
+    }
+
+    #[test]
+    fn house_rules() {
+        let two_wildcards = Rules::new("JT23456789QKA", "JT");
+        assert_eq!(two_wildcards.classify("KTJJT").name, Some(FiveOfAKind));
+        assert_eq!(two_wildcards.classify("32T3K").name, Some(ThreeOfAKind));
+        assert_eq!(Rules::jokers().classify("JJJJJ").name, Some(FiveOfAKind));
+        assert_eq!(
+            Play::parse("32X3K 1", &Rules::standard()),
+            Err(ParseError::UnknownCard('X'))
+        );
+    }
+
+    #[test]
+    fn explain_jokers() {
+        let rules = Rules::jokers();
+        let explanations = explain(parse_plays(INPUT_TEST, &rules).unwrap(), &rules);
+        let last = explanations.last().unwrap();
+        assert_eq!(last.cards, "KTJJT");
+        assert_eq!(last.hand.name, Some(FourOfAKind));
+        assert_eq!(last.substitution, Some('T'));
+        assert_eq!((last.rank, last.winnings), (5, 1100));
+        let total: u64 = explanations.iter().map(|e| e.winnings).sum();
+        assert_eq!(total, 5905);
+        assert_eq!(rules.substitution("JJJJJ"), Some('A'));
+        assert_eq!(rules.substitution("32T3K"), None);
+        let csv = explanation_csv(&explanations);
+        assert_eq!(csv.lines().nth(1), Some("32T3K,Pair,-,1,765,765"));
+    }
+
+    #[test]
+    fn why_hand_beats() {
+        let rules = Rules::standard();
+        let play_a = Play::new("KK677", 0, &rules).unwrap();
+        let play_b = Play::new("KTJJT", 0, &rules).unwrap();
+        let (ordering, reason) = compare(&play_a, &play_b);
+        assert_eq!(ordering, Ordering::Greater);
+        assert_eq!(reason.to_string(), "same hand type, card 2 K beats T");
+        let play_c = Play::new("T55J5", 0, &rules).unwrap();
+        assert_eq!(
+            compare(&play_a, &play_c),
+            (
+                Ordering::Less,
+                Reason::HandType {
+                    winner: rules.classify("T55J5"),
+                    loser: rules.classify("KK677")
+                }
+            )
+        );
+        let why = Report::Why("KK677".to_string(), "KTJJT".to_string());
+        assert_eq!(
+            report(INPUT_TEST, &rules, &why),
+            Ok("KK677 beats KTJJT: same hand type, card 2 K beats T\n".to_string())
+        );
+        let table = report(INPUT_TEST, &rules, &Report::Explain).unwrap();
+        assert_eq!(table.lines().count(), 6);
+        let args = |args: &[&str]| parse_args(args.iter().map(|arg| arg.to_string()));
+        assert_eq!(args(&["--why", "KK677", "KTJJT"]), Ok(Some(why)));
+        assert_eq!(args(&[]), Ok(None));
+        assert!(args(&["--csv", "--explain"]).is_err());
+        assert!(args(&["--why", "KK677"]).is_err());
+    }
+
+    #[test]
+    fn small_hands() {
+        let rules = Rules::standard().with_hand_size(3);
+        let vec_play = parse_plays("AKQ 1\n22A 2\n333 3\n2A2 4", &rules).unwrap();
+        let explanations = explain(vec_play, &rules);
+        let ranked: Vec<&str> = explanations.iter().map(|e| e.cards.as_str()).collect();
+        assert_eq!(ranked, vec!["AKQ", "22A", "2A2", "333"]);
+        assert_eq!(explanations[3].hand.to_string(), "3");
+        assert_eq!(
+            Play::parse("32T3K 1", &rules),
+            Err(ParseError::WrongLength(5))
+        );
+    }
+
+    #[test]
+    fn large_hands() {
+        let rules = Rules::jokers().with_hand_size(7);
+        assert_eq!(rules.classify("33322JJ").counts, vec![5, 2]);
+        assert_eq!(rules.classify("AAKKQQ2").counts, vec![2, 2, 2, 1]);
+        assert!(rules.classify("AAAKKKQ") > rules.classify("AAAKKQQ"));
+        assert!(rules.classify("AAAAKQ2") > rules.classify("AAAKKKQ"));
+        assert_eq!(rules.classify("AAKKQQ2").to_string(), "2+2+2+1");
+        let rules = rules.with_hand_type(vec![2, 2, 2, 1], Custom("ThreePair".to_string()));
+        assert_eq!(rules.classify("AAKKQQ2").to_string(), "ThreePair");
+        assert!(rules.classify("AAKKQQ2") < rules.classify("AAAKKQQ"));
+        assert_eq!(rules.hand_types.len(), 8);
+    }
+}
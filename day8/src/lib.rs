@@ -0,0 +1,373 @@
+use num::Integer;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Instruction {
+    Left,
+    Right,
+}
+
+/// Children of a node, as indices into `Network::names`.
+pub struct Node {
+    pub left: usize,
+    pub right: usize,
+}
+
+pub struct Network<'a> {
+    pub instructions: Vec<Instruction>,
+    pub names: Vec<&'a str>,
+    pub indices: HashMap<&'a str, usize>,
+    pub nodes: Vec<Node>,
+}
+
+/// Matches node names against a pattern of the same length, `?` matching any character.
+pub fn matches_pattern(name: &str, pattern: &str) -> bool {
+    name.len() == pattern.len()
+        && name
+            .chars()
+            .zip(pattern.chars())
+            .all(|(c, p)| p == '?' || c == p)
+}
+
+impl<'a> Network<'a> {
+    pub fn parse(input: &'a str) -> Network<'a> {
+        let mut iterator_split_paragraph = input.split("\n\n");
+        let instructions = iterator_split_paragraph
+            .next()
+            .unwrap()
+            .chars()
+            .map(|c| match c {
+                'L' => Instruction::Left,
+                'R' => Instruction::Right,
+                _ => panic!("Wrong instruction"),
+            })
+            .collect();
+        let nodes_str = iterator_split_paragraph.next().unwrap();
+        let vec_lines: Vec<(&str, &str, &str)> = nodes_str
+            .lines()
+            .map(|line| {
+                let (node_name, tuple_str_l_r) = line.split_once(" = ").unwrap();
+                let (left, right) = tuple_str_l_r
+                    .trim_matches(|c| c == '(' || c == ')')
+                    .split_once(", ")
+                    .unwrap();
+                (node_name, left, right)
+            })
+            .collect();
+        let names: Vec<&str> = vec_lines.iter().map(|&(name, _, _)| name).collect();
+        let indices: HashMap<&str, usize> = names
+            .iter()
+            .enumerate()
+            .map(|(index, &name)| (name, index))
+            .collect();
+        let nodes = vec_lines
+            .iter()
+            .map(|(_, left, right)| Node {
+                left: indices[left],
+                right: indices[right],
+            })
+            .collect();
+        Network {
+            instructions,
+            names,
+            indices,
+            nodes,
+        }
+    }
+    pub fn index(&self, name: &str) -> usize {
+        self.indices[name]
+    }
+    pub fn find_nodes(&self, predicate: impl Fn(&str) -> bool) -> Vec<usize> {
+        (0..self.names.len())
+            .filter(|&node| predicate(self.names[node]))
+            .collect()
+    }
+    fn next(&self, node: usize, step: usize) -> usize {
+        match self.instructions[step % self.instructions.len()] {
+            Instruction::Left => self.nodes[node].left,
+            Instruction::Right => self.nodes[node].right,
+        }
+    }
+    /// Walks from `start` until a (node, instruction offset) state repeats.
+    pub fn find_cycle(&self, start: usize, is_end: impl Fn(&str) -> bool) -> Cycle {
+        let num_instructions = self.instructions.len();
+        let end_nodes: Vec<bool> = self.names.iter().map(|name| is_end(name)).collect();
+        let mut first_seen = vec![None; self.nodes.len() * num_instructions];
+        let mut hits = Vec::new();
+        let mut node = start;
+        let mut step = 0;
+        loop {
+            let offset = step as usize % num_instructions;
+            let state = node * num_instructions + offset;
+            if let Some(cycle_start) = first_seen[state] {
+                let (prefix_hits, cycle_hits) = hits.iter().partition(|&&hit| hit < cycle_start);
+                return Cycle {
+                    start: cycle_start,
+                    length: step - cycle_start,
+                    prefix_hits,
+                    cycle_hits,
+                };
+            }
+            first_seen[state] = Some(step);
+            if end_nodes[node] {
+                hits.push(step);
+            }
+            node = self.next(node, offset);
+            step += 1;
+        }
+    }
+    /// Edges walked again and again once the walk from `start` is in its cycle.
+    pub fn cycle_edges(&self, start: usize, cycle: &Cycle) -> HashSet<(usize, usize)> {
+        let mut node = start;
+        let mut edges = HashSet::new();
+        for step in 0..(cycle.start + cycle.length) {
+            let next = self.next(node, step as usize);
+            if step >= cycle.start {
+                edges.insert((node, next));
+            }
+            node = next;
+        }
+        edges
+    }
+    /// Graphviz description of the network, with the cycle of each walk in its own colour.
+    pub fn to_dot(&self, walks: &[(usize, &Cycle)], is_end: impl Fn(&str) -> bool) -> String {
+        const COLORS: [&str; 6] = ["red", "blue", "darkgreen", "orange", "purple", "brown"];
+        let mut dot = "digraph network {\n".to_string();
+        for (node, name) in self.names.iter().enumerate() {
+            let shape = if is_end(name) {
+                "doublecircle"
+            } else if walks.iter().any(|&(start, _)| start == node) {
+                "box"
+            } else {
+                "circle"
+            };
+            writeln!(dot, "  \"{}\" [shape={}];", name, shape).unwrap();
+        }
+        // An edge shared by several cycles takes the colour of the first walk.
+        let mut colors = HashMap::new();
+        for (i, &(start, cycle)) in walks.iter().enumerate() {
+            for edge in self.cycle_edges(start, cycle) {
+                colors.entry(edge).or_insert(COLORS[i % COLORS.len()]);
+            }
+        }
+        for (node, children) in self.nodes.iter().enumerate() {
+            let edges = if children.left == children.right {
+                vec![(children.left, "LR")]
+            } else {
+                vec![(children.left, "L"), (children.right, "R")]
+            };
+            for (child, label) in edges {
+                write!(
+                    dot,
+                    "  \"{}\" -> \"{}\" [label={}",
+                    self.names[node], self.names[child], label
+                )
+                .unwrap();
+                if let Some(color) = colors.get(&(node, child)) {
+                    write!(dot, ", color={}, penwidth=2", color).unwrap();
+                }
+                dot.push_str("];\n");
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Steps at which a walk is on an end node.
+/// Hits in `cycle_hits` repeat every `length` steps, those in `prefix_hits` happen once.
+#[derive(PartialEq, Eq, Debug)]
+pub struct Cycle {
+    pub start: u64,
+    pub length: u64,
+    pub prefix_hits: Vec<u64>,
+    pub cycle_hits: Vec<u64>,
+}
+
+impl Cycle {
+    pub fn hits_at(&self, step: u64) -> bool {
+        if step < self.start {
+            self.prefix_hits.contains(&step)
+        } else {
+            let step_in_cycle = self.start + (step - self.start) % self.length;
+            self.cycle_hits.contains(&step_in_cycle)
+        }
+    }
+}
+
+/// Solves `x = residue_1 mod modulus_1` and `x = residue_2 mod modulus_2` for non coprime moduli.
+fn crt(residue_1: i128, modulus_1: i128, residue_2: i128, modulus_2: i128) -> Option<(i128, i128)> {
+    let extended = modulus_1.extended_gcd(&modulus_2);
+    let gcd = extended.gcd;
+    if (residue_2 - residue_1) % gcd != 0 {
+        return None;
+    }
+    let modulus = modulus_1.lcm(&modulus_2);
+    let factor = ((residue_2 - residue_1) / gcd * extended.x).mod_floor(&(modulus_2 / gcd));
+    Some((
+        (residue_1 + modulus_1 * factor).mod_floor(&modulus),
+        modulus,
+    ))
+}
+
+/// First step, after the start, where every walk is on an end node at once.
+/// With no walk at all there is nothing to wait for, so that is never too.
+pub fn first_common_hit(cycles: &[Cycle]) -> Option<u64> {
+    if cycles.is_empty() {
+        return None;
+    }
+    let all_hit = |step: u64| cycles.iter().all(|cycle| cycle.hits_at(step));
+    let prefix_candidate = cycles
+        .iter()
+        .flat_map(|cycle| cycle.prefix_hits.iter().copied())
+        .filter(|&step| step > 0 && all_hit(step))
+        .min();
+    // Past every prefix, each walk hits at its cycle hits plus a multiple of its length.
+    let mut solutions: Vec<(i128, i128)> = vec![(0, 1)];
+    for cycle in cycles {
+        solutions = solutions
+            .iter()
+            .flat_map(|&(residue, modulus)| {
+                cycle.cycle_hits.iter().filter_map(move |&hit| {
+                    crt(residue, modulus, hit as i128, cycle.length as i128)
+                })
+            })
+            .collect();
+        solutions.sort();
+        solutions.dedup();
+    }
+    let lower_bound = cycles
+        .iter()
+        .map(|cycle| cycle.start)
+        .max()
+        .unwrap_or(0)
+        .max(1) as i128;
+    let cycle_candidate = solutions
+        .into_iter()
+        .map(|(residue, modulus)| {
+            if residue >= lower_bound {
+                residue
+            } else {
+                residue + (lower_bound - residue + modulus - 1) / modulus * modulus
+            }
+        })
+        .min()
+        .map(|step| step as u64);
+    match (prefix_candidate, cycle_candidate) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ends_with(letter: char) -> impl Fn(&str) -> bool {
+        move |node: &str| node.ends_with(letter)
+    }
+
+    fn ghosts(network: &Network) -> Vec<Cycle> {
+        network
+            .find_nodes(ends_with('A'))
+            .into_iter()
+            .map(|node| network.find_cycle(node, ends_with('Z')))
+            .collect()
+    }
+
+    #[test]
+    fn single_walk() {
+        let network = Network::parse(include_str!("../input_test_2.txt"));
+        let cycle = network.find_cycle(network.index("AAA"), |node| node == "ZZZ");
+        assert_eq!(first_common_hit(&[cycle]), Some(6));
+    }
+
+    #[test]
+    fn ghost_walk() {
+        let network = Network::parse(include_str!("../input_test_3.txt"));
+        let cycle = network.find_cycle(network.index("22A"), ends_with('Z'));
+        assert_eq!(cycle.start, 1);
+        assert_eq!(cycle.length, 6);
+        assert_eq!(cycle.cycle_hits, vec![3, 6]);
+        assert_eq!(first_common_hit(&ghosts(&network)), Some(6));
+    }
+
+    #[test]
+    fn without_lcm_property() {
+        // 11A reaches 11Z after 2 steps then every 3 steps, 22A after 1 step then every 2 steps.
+        let input = "L
+
+11A = (11B, XXX)
+11B = (11Z, XXX)
+11Z = (11C, XXX)
+11C = (11B, XXX)
+22A = (22Z, XXX)
+22Z = (22B, XXX)
+22B = (22Z, XXX)
+XXX = (XXX, XXX)";
+        let network = Network::parse(input);
+        assert_eq!(first_common_hit(&ghosts(&network)), Some(5));
+    }
+
+    #[test]
+    fn prefix_only_and_never() {
+        let input = "L
+
+11A = (11Z, XXX)
+11Z = (XXX, XXX)
+22A = (22Z, XXX)
+22Z = (22B, XXX)
+22B = (22Z, XXX)
+33A = (33B, XXX)
+33B = (33Z, XXX)
+33Z = (33B, XXX)
+XXX = (XXX, XXX)";
+        let network = Network::parse(input);
+        let cycle_1 = network.find_cycle(network.index("11A"), ends_with('Z'));
+        assert_eq!(cycle_1.prefix_hits, vec![1]);
+        assert!(cycle_1.cycle_hits.is_empty());
+        let cycle_2 = network.find_cycle(network.index("22A"), ends_with('Z'));
+        assert_eq!(first_common_hit(&[cycle_1, cycle_2]), Some(1));
+        let cycle_2 = network.find_cycle(network.index("22A"), ends_with('Z'));
+        let cycle_3 = network.find_cycle(network.index("33A"), ends_with('Z'));
+        assert_eq!(first_common_hit(&[cycle_2, cycle_3]), None);
+    }
+
+    #[test]
+    fn no_walks() {
+        assert_eq!(first_common_hit(&[]), None);
+        let network = Network::parse(include_str!("../input_test_3.txt"));
+        let starts = network.find_nodes(|node| matches_pattern(node, "??Q"));
+        assert!(starts.is_empty());
+        let cycles: Vec<Cycle> = starts
+            .into_iter()
+            .map(|node| network.find_cycle(node, ends_with('Z')))
+            .collect();
+        assert_eq!(first_common_hit(&cycles), None);
+    }
+
+    #[test]
+    fn patterns() {
+        assert!(matches_pattern("22A", "??A"));
+        assert!(matches_pattern("AAA", "AAA"));
+        assert!(!matches_pattern("ZZZ", "AAA"));
+        assert!(!matches_pattern("2A", "??A"));
+    }
+
+    #[test]
+    fn dot_export() {
+        let network = Network::parse(include_str!("../input_test_3.txt"));
+        let start = network.index("11A");
+        let cycle = network.find_cycle(start, ends_with('Z'));
+        let dot = network.to_dot(&[(start, &cycle)], ends_with('Z'));
+        assert!(dot.starts_with("digraph network {"));
+        assert!(dot.contains("  \"11A\" [shape=box];"));
+        assert!(dot.contains("  \"11Z\" [shape=doublecircle];"));
+        assert!(dot.contains("  \"22B\" -> \"22C\" [label=LR];"));
+        assert!(dot.contains("  \"11B\" -> \"11Z\" [label=R, color=red, penwidth=2];"));
+        assert!(dot.contains("  \"11Z\" -> \"11B\" [label=L, color=red, penwidth=2];"));
+        assert!(dot.contains("  \"11A\" -> \"11B\" [label=L];"));
+        assert_eq!(dot.matches("\"11B\" -> \"11Z\"").count(), 1);
+    }
+}
@@ -1,62 +1,41 @@
-use num::integer::lcm;
-use std::collections::HashMap;
-
-struct Node {
-    left: String,
-    right: String,
-}
+use day8::{first_common_hit, matches_pattern, Network};
 
+/// Usage: part_2 [--start PATTERN] [--end PATTERN] [--dot]
+/// Patterns match node names character by character, `?` matching anything.
 fn main() {
     // let input = include_str!("../input_test_1.txt");
     // let input = include_str!("../input_test_2.txt");
     // let input = include_str!("../input_test_3.txt");
     let input = include_str!("../input.txt");
-    let mut iterator_split_paragraph = input.split("\n\n");
-    let instructions = iterator_split_paragraph.next().unwrap();
-    let nodes_str = iterator_split_paragraph.next().unwrap();
-    let nodes: HashMap<&str, Node> = nodes_str
-        .split_terminator("\n")
-        .map(|line| {
-            let (node_name, tuple_str_l_r) = line.split_once(" = ").unwrap();
-            let mut iter_string = tuple_str_l_r.split(", ");
-            let (mut left, mut right) = (
-                iter_string.next().unwrap().to_string(),
-                iter_string.next().unwrap().to_string(),
-            );
-            left.retain(|c| c != '(');
-            right.retain(|c| c != ')');
-            (node_name, Node { left, right })
-        })
+    let mut start_pattern = "??A".to_string();
+    let mut end_pattern = "??Z".to_string();
+    let mut dot = false;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--start" => start_pattern = args.next().unwrap(),
+            "--end" => end_pattern = args.next().unwrap(),
+            "--dot" => dot = true,
+            _ => panic!("unknown argument {}", arg),
+        }
+    }
+    let is_end = |node: &str| matches_pattern(node, &end_pattern);
+    let network = Network::parse(input);
+    let walks: Vec<_> = network
+        .find_nodes(|node| matches_pattern(node, &start_pattern))
+        .into_iter()
+        .map(|start| (start, network.find_cycle(start, is_end)))
         .collect();
-    let starting_nodes = nodes.keys().filter(|s| s.chars().nth(2).unwrap() == 'A');
-    let total_num = starting_nodes
-        .map(|&str_starting| {
-            let starting_node = &nodes[str_starting];
-            get_num_instructions(starting_node, instructions, &nodes)
-        })
-        .fold(1, |acc, value| lcm(acc, value));
-    dbg!(total_num);
-}
-
-fn get_num_instructions(
-    starting_node: &Node,
-    instructions: &str,
-    nodes: &HashMap<&str, Node>,
-) -> u64 {
-    let mut num_instructions = 0;
-    let mut current_node = starting_node;
-    for c in instructions.chars().cycle() {
-        let next_str = match c {
-            'L' => current_node.left.as_str(),
-            'R' => current_node.right.as_str(),
-            _ => panic!("Wrong instruction"),
-        };
-        num_instructions += 1;
-        if next_str.chars().nth(2).unwrap() == 'Z' {
-            break;
-        } else {
-            current_node = &nodes[next_str];
+    if dot {
+        let walk_refs: Vec<_> = walks.iter().map(|(start, cycle)| (*start, cycle)).collect();
+        print!("{}", network.to_dot(&walk_refs, is_end));
+        return;
+    }
+    let cycles: Vec<_> = walks.into_iter().map(|(_, cycle)| cycle).collect();
+    match first_common_hit(&cycles) {
+        Some(total_num) => {
+            dbg!(total_num);
         }
+        None => println!("the ghosts never all reach an end node together"),
     }
-    num_instructions
 }
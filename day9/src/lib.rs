@@ -0,0 +1,305 @@
+use num::{BigInt, Zero};
+use std::fmt;
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum FitError {
+    Empty,
+    /// No difference row was all zero among the first `levels` ones.
+    NotPolynomial {
+        levels: usize,
+    },
+}
+
+impl fmt::Display for FitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FitError::Empty => write!(f, "no values"),
+            FitError::NotPolynomial { levels } => {
+                write!(f, "not polynomial within {} levels", levels)
+            }
+        }
+    }
+}
+
+/// Polynomial through a sequence, in Newton form: `differences[j]` is the first
+/// element of the j-th difference row, so `p(k) = sum(differences[j] * C(k, j))`.
+#[derive(PartialEq, Eq, Debug)]
+pub struct Polynomial {
+    pub differences: Vec<BigInt>,
+}
+
+impl Polynomial {
+    /// Fits the values at indices `0..values.len()`, computing at most `max_levels` difference rows.
+    /// Fails when none of them is all zero, as the degree is then unknown.
+    pub fn fit(values: &[i64], max_levels: usize) -> Result<Polynomial, FitError> {
+        if values.is_empty() {
+            return Err(FitError::Empty);
+        }
+        let mut differences = Vec::new();
+        let mut vec_diff: Vec<BigInt> = values.iter().map(|&value| BigInt::from(value)).collect();
+        for level in 0..=max_levels {
+            if vec_diff.is_empty() {
+                return Err(FitError::NotPolynomial { levels: level - 1 });
+            }
+            if vec_diff.iter().all(|value| value.is_zero()) {
+                return Ok(Polynomial { differences });
+            }
+            differences.push(vec_diff[0].clone());
+            vec_diff = construct_diff(&vec_diff);
+        }
+        Err(FitError::NotPolynomial { levels: max_levels })
+    }
+    pub fn degree(&self) -> usize {
+        self.differences.len().saturating_sub(1)
+    }
+    /// Value at index `k`, which may be past either end of the fitted values.
+    pub fn evaluate(&self, k: i64) -> BigInt {
+        let k = BigInt::from(k);
+        let mut binomial = BigInt::from(1);
+        let mut value = BigInt::zero();
+        for (j, difference) in self.differences.iter().enumerate() {
+            value += difference * &binomial;
+            // C(k, j + 1) = C(k, j) * (k - j) / (j + 1), always an exact division
+            binomial = binomial * (&k - j) / (j + 1);
+        }
+        value
+    }
+}
+
+pub fn construct_diff(vec_input: &[BigInt]) -> Vec<BigInt> {
+    vec_input
+        .windows(2)
+        .map(|chunk| &chunk[1] - &chunk[0])
+        .collect()
+}
+
+pub fn parse_line(line: &str) -> Vec<i64> {
+    line.split_whitespace()
+        .map(|n_s| n_s.parse::<i64>().unwrap())
+        .collect()
+}
+
+/// How far, and in which direction, each line is extrapolated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// Past the end of the line when set, before its start otherwise.
+    pub forward: bool,
+    pub steps: i64,
+    pub max_levels: usize,
+}
+
+impl Options {
+    /// One step, without a limit on the difference levels.
+    pub fn new(forward: bool) -> Options {
+        Options {
+            forward,
+            steps: 1,
+            max_levels: usize::MAX,
+        }
+    }
+}
+
+/// The degree and extrapolated value of one line, or why it was left out.
+pub type LineReport = Result<(usize, BigInt), FitError>;
+
+pub fn extrapolate(input: &str, options: &Options) -> Vec<LineReport> {
+    input
+        .lines()
+        .map(|line| {
+            let values = parse_line(line);
+            let polynomial = Polynomial::fit(&values, options.max_levels)?;
+            let index = if options.forward {
+                values.len() as i64 - 1 + options.steps
+            } else {
+                -options.steps
+            };
+            Ok((polynomial.degree(), polynomial.evaluate(index)))
+        })
+        .collect()
+}
+
+/// Sum of the extrapolated values, leaving out the flagged lines.
+pub fn sum_extrapolated(reports: &[LineReport]) -> BigInt {
+    reports
+        .iter()
+        .filter_map(|report| report.as_ref().ok())
+        .map(|(_, value)| value)
+        .sum()
+}
+
+pub const USAGE: &str = "Usage: [--summary] [--max-levels N] [STEPS]";
+
+/// Command line of both parts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Args {
+    pub options: Options,
+    /// Report every line, not only those left out of the sum.
+    pub summary: bool,
+}
+
+/// Parses the arguments shared by both parts, which only differ in `forward`.
+pub fn parse_args(args: impl IntoIterator<Item = String>, forward: bool) -> Result<Args, String> {
+    let mut parsed = Args {
+        options: Options::new(forward),
+        summary: false,
+    };
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--summary" => parsed.summary = true,
+            "--max-levels" => {
+                let levels = args.next().unwrap_or_default();
+                parsed.options.max_levels = levels
+                    .parse()
+                    .map_err(|_| format!("--max-levels expects a number, got {levels:?}"))?;
+            }
+            _ if arg.starts_with("--") => return Err(format!("unknown flag {arg}")),
+            _ => {
+                parsed.options.steps = arg
+                    .parse()
+                    .map_err(|_| format!("STEPS expects a number, got {arg:?}"))?
+            }
+        }
+    }
+    Ok(parsed)
+}
+
+/// Lines left out of the sum, or with `summary` every line and a count of those left out.
+pub fn diagnostics(reports: &[LineReport], summary: bool) -> String {
+    let mut diagnostics = String::new();
+    for (line_i, report) in reports.iter().enumerate() {
+        match report {
+            Ok((degree, value)) if summary => {
+                diagnostics += &format!("line {}: degree {}, value {}\n", line_i + 1, degree, value)
+            }
+            Ok(_) => (),
+            Err(error) => {
+                diagnostics += &format!("line {}: {}, left out of the sum\n", line_i + 1, error)
+            }
+        }
+    }
+    if summary {
+        let num_flagged = reports.iter().filter(|report| report.is_err()).count();
+        diagnostics += &format!("{} lines left out\n", num_flagged);
+    }
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fit_line(line: &str) -> Polynomial {
+        Polynomial::fit(&parse_line(line), usize::MAX).unwrap()
+    }
+
+    #[test]
+    fn next_and_previous() {
+        let input = include_str!("../input_test.txt");
+        let lines: Vec<_> = input.lines().map(parse_line).collect();
+        let polynomials: Vec<_> = lines
+            .iter()
+            .map(|values| Polynomial::fit(values, usize::MAX).unwrap())
+            .collect();
+        let next: Vec<_> = lines
+            .iter()
+            .zip(polynomials.iter())
+            .map(|(values, p)| p.evaluate(values.len() as i64))
+            .collect();
+        let previous: Vec<_> = polynomials.iter().map(|p| p.evaluate(-1)).collect();
+        assert_eq!(next, [18, 28, 68, 23].map(BigInt::from));
+        assert_eq!(previous, [-3, 0, 5, 5].map(BigInt::from));
+        let degrees: Vec<_> = polynomials.iter().map(|p| p.degree()).collect();
+        assert_eq!(degrees, vec![1, 2, 3, 2]);
+    }
+
+    #[test]
+    fn far_away() {
+        // squares: k^2 at index k
+        let squares = fit_line("0 1 4 9 16");
+        assert_eq!(squares.degree(), 2);
+        assert_eq!(
+            squares.evaluate(1_000_000),
+            BigInt::from(1_000_000_000_000i64)
+        );
+        assert_eq!(squares.evaluate(-7), BigInt::from(49));
+        // k^3 at index k, far enough for the result to overflow i64
+        let cubes = fit_line("0 1 8 27 64 125");
+        let k = 10_000_000i64;
+        assert_eq!(cubes.evaluate(k), BigInt::from(k).pow(3));
+        assert_eq!(fit_line("0 0 0").degree(), 0);
+        assert_eq!(fit_line("0 0 0").evaluate(-5), BigInt::zero());
+    }
+
+    #[test]
+    fn diagnostics() {
+        assert_eq!(
+            Polynomial::fit(&[1, 2, 4, 8], usize::MAX),
+            Err(FitError::NotPolynomial { levels: 3 })
+        );
+        assert_eq!(
+            Polynomial::fit(&[7], usize::MAX),
+            Err(FitError::NotPolynomial { levels: 0 })
+        );
+        assert_eq!(Polynomial::fit(&[], usize::MAX), Err(FitError::Empty));
+        let cubic = parse_line("0 1 8 27 64 125");
+        assert_eq!(Polynomial::fit(&cubic, 4).unwrap().degree(), 3);
+        assert_eq!(
+            Polynomial::fit(&cubic, 3),
+            Err(FitError::NotPolynomial { levels: 3 })
+        );
+        assert_eq!(
+            FitError::NotPolynomial { levels: 3 }.to_string(),
+            "not polynomial within 3 levels"
+        );
+    }
+
+    #[test]
+    fn options() {
+        let input = "0 3 6 9 12 15\n1 2 4 8";
+        let mut options = Options::new(true);
+        let reports = extrapolate(input, &options);
+        assert_eq!(reports[0], Ok((1, BigInt::from(18))));
+        assert_eq!(reports[1], Err(FitError::NotPolynomial { levels: 3 }));
+        assert_eq!(sum_extrapolated(&reports), BigInt::from(18));
+        options.forward = false;
+        options.steps = 2;
+        assert_eq!(extrapolate(input, &options)[0], Ok((1, BigInt::from(-6))));
+        options.max_levels = 1;
+        assert_eq!(
+            extrapolate(input, &options)[0],
+            Err(FitError::NotPolynomial { levels: 1 })
+        );
+    }
+
+    #[test]
+    fn command_line() {
+        let args =
+            |args: &[&str], forward| parse_args(args.iter().map(|arg| arg.to_string()), forward);
+        let parsed = args(&["--summary", "--max-levels", "4", "-3"], false).unwrap();
+        assert_eq!(
+            parsed.options,
+            Options {
+                forward: false,
+                steps: -3,
+                max_levels: 4
+            }
+        );
+        assert!(parsed.summary);
+        assert_eq!(args(&[], true).unwrap().options, Options::new(true));
+        assert_eq!(
+            args(&["--sumary"], true),
+            Err("unknown flag --sumary".to_string())
+        );
+        assert!(args(&["--max-levels"], true).is_err());
+        let reports = extrapolate("0 3 6 9 12 15\n1 2 4 8", &Options::new(true));
+        assert_eq!(
+            diagnostics(&reports, false),
+            format!(
+                "line 2: {}, left out of the sum\n",
+                reports[1].as_ref().unwrap_err()
+            )
+        );
+        assert_eq!(diagnostics(&reports, true).lines().count(), 3);
+    }
+}